@@ -0,0 +1,357 @@
+use std::{fmt, str};
+
+use crate::*;
+
+/// Represents typed action performed by [`Unit`], [`CityTile`] or annotation
+/// drawn on visualizer
+///
+/// Converts into wire format defined by [`Commands`] with [`fmt::Display`]
+/// and parses back with [`str::FromStr`]
+///
+/// # Examples
+///
+/// ```
+/// # use lux_ai_api::*;
+/// # let unit = Unit::new(0, UnitType::Worker, "u_1".to_string(), Position::new(0, 0), 0.0);
+/// let action = unit.move_(Direction::North);
+/// assert_eq!(action.to_string(), "m u_1 n");
+/// assert_eq!("m u_1 n".parse::<ActionKind>()?, action);
+/// # Ok::<(), LuxAiError>(())
+/// ```
+///
+/// # See also
+///
+/// Check <https://www.lux-ai.org/specs-2021#Units>
+/// Check <https://www.lux-ai.org/specs-2021#CityTiles>
+#[derive(Clone, PartialEq, Eq, Hash, fmt::Debug)]
+pub enum ActionKind {
+    /// Move unit to given direction
+    Move {
+        /// Id of moving [`Unit`]
+        unit_id:   EntityId,
+        /// [`Direction`] to move to
+        direction: Direction,
+    },
+
+    /// Transfer resource from unit to adjacent unit
+    Transfer {
+        /// Id of [`Unit`] transfering resource
+        unit_id:        EntityId,
+        /// Id of [`Unit`] receiving resource
+        destination_id: EntityId,
+        /// Type of transfering resource
+        resource_type:  ResourceType,
+        /// Amount of transfering resource, clamped to source cargo and
+        /// destination cargo space left
+        amount:         ResourceAmount,
+    },
+
+    /// Build [`CityTile`] on unit's position
+    BuildCity {
+        /// Id of building [`Unit`]
+        unit_id: EntityId,
+    },
+
+    /// Pillage road on unit's position
+    Pillage {
+        /// Id of pillaging [`Unit`]
+        unit_id: EntityId,
+    },
+
+    /// Research by [`CityTile`]
+    Research {
+        /// [`Position`] of researching [`CityTile`]
+        position: Position,
+    },
+
+    /// Build worker by [`CityTile`]
+    BuildWorker {
+        /// [`Position`] of building [`CityTile`]
+        position: Position,
+    },
+
+    /// Build cart by [`CityTile`]
+    BuildCart {
+        /// [`Position`] of building [`CityTile`]
+        position: Position,
+    },
+
+    /// Annotate circle on point
+    AnnotateCircle {
+        /// [`Position`] of circle center
+        position: Position,
+    },
+
+    /// Annotate cross on point
+    AnnotateCross {
+        /// [`Position`] of cross center
+        position: Position,
+    },
+
+    /// Annotate line between two points
+    AnnotateLine {
+        /// Line start point
+        from: Position,
+        /// Line end point
+        to:   Position,
+    },
+
+    /// Annotate text on point
+    AnnotateText {
+        /// [`Position`] of text
+        position:  Position,
+        /// Text to annotate
+        message:   String,
+        /// Message font size
+        font_size: i32,
+    },
+
+    /// Annotate side text
+    AnnotateSideText {
+        /// Text to annotate
+        message: String,
+    },
+}
+
+/// Convert into wire format defined by [`Commands`]
+impl fmt::Display for ActionKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Move { unit_id, direction } => write!(
+                f,
+                "{} {} {}",
+                Commands::MOVE,
+                unit_id,
+                direction.to_argument()
+            ),
+            Self::Transfer {
+                unit_id,
+                destination_id,
+                resource_type,
+                amount,
+            } => write!(
+                f,
+                "{} {} {} {} {}",
+                Commands::TRANSFER,
+                unit_id,
+                destination_id,
+                resource_type.to_argument(),
+                amount
+            ),
+            Self::BuildCity { unit_id } => write!(f, "{} {}", Commands::BUILD_CITY, unit_id),
+            Self::Pillage { unit_id } => write!(f, "{} {}", Commands::PILLAGE, unit_id),
+            Self::Research { position } =>
+                write!(f, "{} {}", Commands::RESEARCH, position.to_argument()),
+            Self::BuildWorker { position } =>
+                write!(f, "{} {}", Commands::BUILD_WORKER, position.to_argument()),
+            Self::BuildCart { position } =>
+                write!(f, "{} {}", Commands::BUILD_CART, position.to_argument()),
+            Self::AnnotateCircle { position } => write!(
+                f,
+                "{} {}",
+                Commands::ANNOTATE_CIRCLE,
+                position.to_argument()
+            ),
+            Self::AnnotateCross { position } =>
+                write!(f, "{} {}", Commands::ANNOTATE_CROSS, position.to_argument()),
+            Self::AnnotateLine { from, to } => write!(
+                f,
+                "{} {} {}",
+                Commands::ANNOTATE_LINE,
+                from.to_argument(),
+                to.to_argument()
+            ),
+            Self::AnnotateText {
+                position,
+                message,
+                font_size,
+            } => write!(
+                f,
+                "{} {} '{}' {}",
+                Commands::ANNOTATE_TEXT,
+                position.to_argument(),
+                message,
+                font_size
+            ),
+            Self::AnnotateSideText { message } =>
+                write!(f, "{} '{}'", Commands::ANNOTATE_SIDE_TEXT, message),
+        }
+    }
+}
+
+/// Parse from wire format defined by [`Commands`]
+impl str::FromStr for ActionKind {
+    type Err = LuxAiError;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        let (string, message) = Self::split_message(string);
+        let command = Command::new(string.to_string());
+        let position = |x_idx: usize| -> LuxAiResult<Position> {
+            Ok(Position::new(
+                command.argument::<Coordinate>(x_idx)?,
+                command.argument::<Coordinate>(x_idx + 1)?,
+            ))
+        };
+        let message = || -> LuxAiResult<String> {
+            message.ok_or_else(|| LuxAiError::CommandFormat(command.arguments.clone()))
+        };
+
+        let action = match command.argument::<String>(0)?.as_str() {
+            Commands::MOVE => {
+                command.expect_arguments(3)?;
+                Self::Move {
+                    unit_id:   command.argument(1)?,
+                    direction: command.argument(2)?,
+                }
+            },
+            Commands::TRANSFER => {
+                command.expect_arguments(5)?;
+                Self::Transfer {
+                    unit_id:        command.argument(1)?,
+                    destination_id: command.argument(2)?,
+                    resource_type:  command.argument(3)?,
+                    amount:         command.argument(4)?,
+                }
+            },
+            Commands::BUILD_CITY => {
+                command.expect_arguments(2)?;
+                Self::BuildCity {
+                    unit_id: command.argument(1)?,
+                }
+            },
+            Commands::PILLAGE => {
+                command.expect_arguments(2)?;
+                Self::Pillage {
+                    unit_id: command.argument(1)?,
+                }
+            },
+            Commands::RESEARCH => {
+                command.expect_arguments(3)?;
+                Self::Research {
+                    position: position(1)?,
+                }
+            },
+            Commands::BUILD_WORKER => {
+                command.expect_arguments(3)?;
+                Self::BuildWorker {
+                    position: position(1)?,
+                }
+            },
+            Commands::BUILD_CART => {
+                command.expect_arguments(3)?;
+                Self::BuildCart {
+                    position: position(1)?,
+                }
+            },
+            Commands::ANNOTATE_CIRCLE => {
+                command.expect_arguments(3)?;
+                Self::AnnotateCircle {
+                    position: position(1)?,
+                }
+            },
+            Commands::ANNOTATE_CROSS => {
+                command.expect_arguments(3)?;
+                Self::AnnotateCross {
+                    position: position(1)?,
+                }
+            },
+            Commands::ANNOTATE_LINE => {
+                command.expect_arguments(5)?;
+                Self::AnnotateLine {
+                    from: position(1)?,
+                    to:   position(3)?,
+                }
+            },
+            Commands::ANNOTATE_TEXT => {
+                command.expect_arguments(4)?;
+                Self::AnnotateText {
+                    position:  position(1)?,
+                    message:   message()?,
+                    font_size: command.argument(3)?,
+                }
+            },
+            Commands::ANNOTATE_SIDE_TEXT => {
+                command.expect_arguments(1)?;
+                Self::AnnotateSideText {
+                    message: message()?,
+                }
+            },
+            _ => Err(LuxAiError::CommandFormat(command.arguments.clone()))?,
+        };
+        Ok(action)
+    }
+}
+
+impl ActionKind {
+    /// Returns id of [`Unit`] performing this action
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// [`EntityId`] of [`Unit`] or `None` for [`CityTile`] actions and
+    /// annotations
+    pub fn unit_id(&self) -> Option<&EntityId> {
+        match self {
+            Self::Move { unit_id, .. } |
+            Self::Transfer { unit_id, .. } |
+            Self::BuildCity { unit_id } |
+            Self::Pillage { unit_id } => Some(unit_id),
+            _ => None,
+        }
+    }
+
+    /// Returns [`Position`] of [`CityTile`] performing this action
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// [`Position`] of [`CityTile`] or `None` for [`Unit`] actions and
+    /// annotations
+    pub fn city_tile_position(&self) -> Option<Position> {
+        match self {
+            Self::Research { position } |
+            Self::BuildWorker { position } |
+            Self::BuildCart { position } => Some(*position),
+            _ => None,
+        }
+    }
+
+    /// Whether or not this action is an annotation, i.e. affects only
+    /// visualizer
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// `bool` value
+    pub fn is_annotation(&self) -> bool {
+        matches!(
+            self,
+            Self::AnnotateCircle { .. } |
+                Self::AnnotateCross { .. } |
+                Self::AnnotateLine { .. } |
+                Self::AnnotateText { .. } |
+                Self::AnnotateSideText { .. }
+        )
+    }
+
+    /// Splits quoted message out of annotation line, so rest of line can be
+    /// tokenized by whitespaces
+    fn split_message(string: &str) -> (String, Option<String>) {
+        match (string.find('\''), string.rfind('\'')) {
+            (Some(start), Some(end)) if start < end => (
+                format!("{}{}", &string[..start], &string[end + 1..]),
+                Some(string[start + 1..end].to_string()),
+            ),
+            _ => (string.to_string(), None),
+        }
+    }
+}
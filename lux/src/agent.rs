@@ -1,4 +1,5 @@
-use std::fmt;
+use std::{fmt,
+          io::{BufRead, Write}};
 
 use crate::*;
 
@@ -20,6 +21,16 @@ pub struct Agent {
 
     /// List of all players participating in match
     pub players: Vec<Player>,
+
+    /// Units and cities tracked across turns along with changes of last turn
+    pub world: WorldModel,
+
+    /// Units of both teams by position at current turn
+    pub unit_index: UnitIndex,
+
+    /// Unknown commands of current turn, kept with
+    /// [`UnknownCommandPolicy::Collect`] policy
+    pub unknown_commands: Vec<Command>,
 }
 
 impl Agent {
@@ -33,7 +44,7 @@ impl Agent {
     ///
     /// # Returns
     /// Initialized `Agent` or error
-    pub fn new(environment: &mut Environment) -> LuxAiResult<Self> {
+    pub fn new<R: BufRead, W: Write>(environment: &mut Environment<R, W>) -> LuxAiResult<Self> {
         let team = Self::read_team(environment)?;
         let (width, height) = Self::read_map_dimensions(environment)?;
         let game_map = GameMap::new(width, height);
@@ -47,6 +58,9 @@ impl Agent {
             turn,
             game_map,
             players,
+            world: WorldModel::new(),
+            unit_index: UnitIndex::new(),
+            unknown_commands: vec![],
         })
     }
 
@@ -61,10 +75,24 @@ impl Agent {
     /// `Player` reference
     pub fn player(&self) -> &Player { &self.players[self.team as usize] }
 
+    /// Returns day/night cycle calendar at current turn
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// [`GameClock`] value
+    pub fn clock(&self) -> GameClock { GameClock::new(self.turn) }
+
     /// Updates Agent's map for current turn
     /// - updates turn
     /// - reads research points for all `Player`'s
     /// - reads ALL units, resources, cities, city tiles and roads on `GameMap`
+    /// - passes unknown commands to [`Environment::handle_unknown_command`]
+    /// - updates `WorldModel` with changes since previous turn
+    /// - rebuilds `UnitIndex` of units by position
     ///
     /// # Parameters
     ///
@@ -74,26 +102,27 @@ impl Agent {
     /// # Returns
     ///
     /// Nothing or error
-    pub fn update_turn(&mut self, environment: &mut Environment) -> LuxAiResult {
+    pub fn update_turn<R: BufRead, W: Write>(
+        &mut self, environment: &mut Environment<R, W>,
+    ) -> LuxAiResult {
         self.turn += 1;
         self.reset_players_state();
         self.game_map.reset_state();
+        self.unknown_commands.clear();
 
         loop {
             let command = environment.read_command()?;
-            match command.argument::<String>(0)?.as_str() {
-                Commands::DONE => break,
-                Commands::RESEARCH_POINTS => self.update_research_points(&command)?,
-                Commands::RESOURCES => self.update_resources(&command)?,
-                Commands::UNITS => self.update_unit(&command)?,
-                Commands::CITY => self.update_city(&command)?,
-                Commands::CITY_TILES => self.update_city_tile(&command)?,
-                Commands::ROADS => self.update_road(&command)?,
-                _ => continue,
+            match InputLine::from_command(&command, environment.line(), self.turn)? {
+                Some(input) => match InputMessage::from_input(&input)? {
+                    InputMessage::Done => break,
+                    message => self.apply_message(message, &input)?,
+                },
+                None => environment.handle_unknown_command(self, command)?,
             };
         }
 
-        self.fix_dependencies();
+        self.world.update(self.turn, &self.game_map, &self.players);
+        self.unit_index.update(&self.players);
         Ok(())
     }
 
@@ -103,130 +132,225 @@ impl Agent {
         }
     }
 
-    fn read_team(environment: &mut Environment) -> LuxAiResult<TeamId> {
-        let command = environment.read_len_command(1)?;
+    fn read_team<R: BufRead, W: Write>(environment: &mut Environment<R, W>) -> LuxAiResult<TeamId> {
+        let command = environment.read_command()?;
+        let input = InputLine::new(&command, environment.line(), 0, 1)?;
 
-        let team_id = command.argument(0)?;
+        let team_id = input.token(0)?;
 
         Ok(team_id)
     }
 
-    fn read_map_dimensions(environment: &mut Environment) -> LuxAiResult<(Coordinate, Coordinate)> {
-        let command = environment.read_len_command(2)?;
+    fn read_map_dimensions<R: BufRead, W: Write>(
+        environment: &mut Environment<R, W>,
+    ) -> LuxAiResult<(Coordinate, Coordinate)> {
+        let command = environment.read_command()?;
+        let input = InputLine::new(&command, environment.line(), 0, 2)?;
 
-        let (width, height) = (command.argument(0)?, command.argument(1)?);
+        let (width, height) = (input.token(0)?, input.token(1)?);
 
         Ok((width, height))
     }
 
-    fn update_research_points(&mut self, command: &Command) -> LuxAiResult {
-        command.expect_arguments(3)?;
-        let (team_id, research_points) = (command.argument::<TeamId>(1)?, command.argument(2)?);
-
-        self.players[team_id as usize].research_points = research_points;
-
-        Ok(())
-    }
-
-    fn update_resources(&mut self, command: &Command) -> LuxAiResult {
-        command.expect_arguments(5)?;
-        let (resource_type, x, y, amount) = (
-            command.argument(1)?,
-            command.argument::<Coordinate>(2)?,
-            command.argument::<Coordinate>(3)?,
-            command.argument(4)?,
-        );
-
-        let position = Position::new(x, y);
-        let resource = Resource::new(resource_type, amount);
-
-        self.game_map[position].resource = Some(resource);
-
-        Ok(())
-    }
-
-    fn update_unit(&mut self, command: &Command) -> LuxAiResult {
-        command.expect_arguments(10)?;
-        let (unit_type, team, unit_id, x, y, cooldown) = (
-            command.argument(1)?,
-            command.argument(2)?,
-            command.argument(3)?,
-            command.argument::<Coordinate>(4)?,
-            command.argument::<Coordinate>(5)?,
-            command.argument(6)?,
-        );
-        let argument_offset = 7;
-
-        let position = Position::new(x, y);
-        let mut unit = Unit::new(team, unit_type, unit_id, position, cooldown);
-        for (index, resource_type) in ResourceType::VALUES.iter().enumerate() {
-            let amount = command.argument(argument_offset + index)?;
-            unit.cargo[*resource_type] = amount;
+    fn apply_message(&mut self, message: InputMessage, input: &InputLine) -> LuxAiResult {
+        let width = self.game_map.width();
+        match message {
+            InputMessage::ResearchPoints {
+                team,
+                research_points,
+            } => {
+                let player = self
+                    .players
+                    .get_mut(team as usize)
+                    .ok_or_else(|| input.error(1))?;
+                player.research_points = research_points;
+            },
+            InputMessage::Resource {
+                resource_type,
+                position,
+                amount,
+            } => {
+                let cell = self
+                    .game_map
+                    .get_mut(position)
+                    .ok_or_else(|| input.position_error(2, position, width))?;
+                cell.resource = Some(Resource::new(resource_type, amount));
+            },
+            InputMessage::Unit {
+                unit_type,
+                team,
+                unit_id,
+                position,
+                cooldown,
+                cargo,
+            } => {
+                if !self.game_map.contains(position) {
+                    return Err(input.position_error(4, position, width).into());
+                }
+                let player = self
+                    .players
+                    .get_mut(team as usize)
+                    .ok_or_else(|| input.error(2))?;
+                let mut unit = Unit::new(team, unit_type, unit_id, position, cooldown);
+                unit.cargo = cargo;
+                player.units.push(unit);
+            },
+            InputMessage::City {
+                team,
+                city_id,
+                fuel,
+                light_upkeep,
+            } => {
+                let player = self
+                    .players
+                    .get_mut(team as usize)
+                    .ok_or_else(|| input.error(1))?;
+                let city = City::new(team, city_id.clone(), fuel, light_upkeep);
+                player.cities.insert(city_id, city);
+            },
+            InputMessage::CityTile {
+                team,
+                city_id,
+                position,
+                cooldown,
+            } => {
+                if !self.game_map.contains(position) {
+                    return Err(input.position_error(3, position, width).into());
+                }
+                let player = self
+                    .players
+                    .get_mut(team as usize)
+                    .ok_or_else(|| input.error(1))?;
+                let city = player
+                    .cities
+                    .get_mut(&city_id)
+                    .ok_or(LuxAiError::CityNotExists(city_id))?;
+                city.add_city_tile(&mut self.game_map, position, cooldown);
+            },
+            InputMessage::Road { position, road } => {
+                let cell = self
+                    .game_map
+                    .get_mut(position)
+                    .ok_or_else(|| input.position_error(1, position, width))?;
+                cell.road = road;
+            },
+            InputMessage::Done | InputMessage::Unknown(_) => {},
         }
-        self.players[team as usize].units.push(unit);
-
         Ok(())
     }
+}
 
-    fn update_city(&mut self, command: &Command) -> LuxAiResult {
-        command.expect_arguments(5)?;
-        let (team_id, city_id, fuel, light_up_keep) = (
-            command.argument(1)?,
-            command.argument::<EntityId>(2)?,
-            command.argument(3)?,
-            command.argument(4)?,
-        );
-
-        let city = City::new(team_id, city_id.clone(), fuel, light_up_keep);
-        self.players[team_id as usize].cities.insert(city_id, city);
-
-        Ok(())
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use super::*;
+
+    const TURN_INPUT: &str = concat!(
+        "0\n",
+        "12 12\n",
+        "rp 0 50\n",
+        "rp 1 0\n",
+        "r wood 0 1 300\n",
+        "r coal 5 5 400\n",
+        "u 0 0 u_1 2 3 1 10 5 0\n",
+        "u 1 1 u_2 8 8 0 0 0 0\n",
+        "c 0 c_1 120 23\n",
+        "ct 0 c_1 2 2 4\n",
+        "ccd 2 2 6\n",
+        "D_DONE\n",
+    );
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn agent_is_send_and_sync() { assert_send_sync::<Agent>(); }
+
+    #[test]
+    fn update_turn_reads_turn_from_memory() {
+        let mut environment = Environment::with_io(io::Cursor::new(TURN_INPUT), Vec::new());
+        let mut agent = Agent::new(&mut environment).unwrap();
+        agent.update_turn(&mut environment).unwrap();
+
+        assert_eq!(agent.team, 0);
+        assert_eq!(agent.turn, 1);
+        assert_eq!(agent.game_map.dimensions::<Coordinate>(), (12, 12));
+        assert_eq!(agent.players[0].research_points, 50);
+        assert!(agent.players[0].is_researched(ResourceType::Coal));
+
+        let wood = agent.game_map[Position::new(0, 1)]
+            .resource
+            .as_ref()
+            .unwrap();
+        assert_eq!(wood.resource_type, ResourceType::Wood);
+        assert_eq!(wood.amount, 300);
+        assert!(agent.game_map[Position::new(5, 5)].has_resource());
+
+        let worker = &agent.players[0].units[0];
+        assert_eq!(worker.id, "u_1");
+        assert_eq!(worker.unit_type, UnitType::Worker);
+        assert_eq!(worker.pos, Position::new(2, 3));
+        assert_eq!(worker.cooldown, 1.0);
+        assert_eq!((worker.cargo.wood, worker.cargo.coal), (10, 5));
+        assert_eq!(agent.players[1].units[0].unit_type, UnitType::Cart);
+
+        let city = &agent.players[0].cities["c_1"];
+        assert_eq!((city.fuel, city.light_upkeep), (120.0, 23.0));
+        let city_tile = agent.game_map.city_tile_at(Position::new(2, 2)).unwrap();
+        assert_eq!(city_tile.cityid, "c_1");
+        assert_eq!(city_tile.cooldown, 4.0);
+        assert_eq!(agent.game_map[Position::new(2, 2)].road, 6.0);
+
+        assert!(matches!(
+            agent.update_turn(&mut environment),
+            Err(LuxAiError::EmptyInput)
+        ));
     }
 
-    fn update_city_tile(&mut self, command: &Command) -> LuxAiResult {
-        command.expect_arguments(6)?;
-
-        let (team_id, city_id, x, y, cooldown) = (
-            command.argument::<TeamId>(1)?,
-            command.argument::<EntityId>(2)?,
-            command.argument::<Coordinate>(3)?,
-            command.argument::<Coordinate>(4)?,
-            command.argument(5)?,
-        );
-
-        let position = Position::new(x, y);
-        let city = self.players[team_id as usize]
-            .cities
-            .get_mut(&city_id)
-            .ok_or(LuxAiError::CityNotExists(city_id))?;
-        city.add_city_tile(position, cooldown);
-
-        Ok(())
+    fn input_error(line: &str) -> InputFormatError {
+        let input = format!("0\n12 12\n{}\nD_DONE\n", line);
+        let mut environment = Environment::with_io(io::Cursor::new(input), Vec::new());
+        let mut agent = Agent::new(&mut environment).unwrap();
+        match agent.update_turn(&mut environment) {
+            Err(LuxAiError::InputFormat(error)) => error,
+            result => panic!("`{}` read as {:?}", line, result),
+        }
     }
 
-    fn update_road(&mut self, command: &Command) -> LuxAiResult {
-        command.expect_arguments(4)?;
-        let (x, y, road) = (
-            command.argument::<Coordinate>(1)?,
-            command.argument::<Coordinate>(2)?,
-            command.argument(3)?,
-        );
+    #[test]
+    fn update_turn_reports_malformed_line() {
+        let error = input_error("rp 0");
+        assert_eq!((error.line, error.turn, error.arity), (3, 1, 3));
+        assert_eq!((error.index, error.token), (2, None));
 
-        let position = Position::new(x, y);
-        let cell = &mut self.game_map[position];
-        cell.road = road;
+        let error = input_error("rp 0 50 1");
+        assert_eq!((error.index, error.token), (3, Some("1".to_string())));
 
-        Ok(())
+        let error = input_error("r wood x 1 300");
+        assert_eq!((error.index, error.token), (2, Some("x".to_string())));
     }
 
-    fn fix_dependencies(&mut self) {
-        for player in self.players.iter_mut() {
-            for (_city_id, city) in player.cities.iter_mut() {
-                for city_tile in city.citytiles.iter() {
-                    let position = city_tile.borrow().pos;
-                    self.game_map[position].citytile = Some(city_tile.clone());
-                }
-            }
+    #[test]
+    fn update_turn_reports_out_of_range_token() {
+        let cases = [
+            ("rp 5 3", 1, "5"),
+            ("r wood 40 40 100", 2, "40"),
+            ("r wood 0 12 100", 3, "12"),
+            ("u 0 5 u_1 2 3 0 0 0 0", 2, "5"),
+            ("u 0 0 u_1 -1 3 0 0 0 0", 4, "-1"),
+            ("c 2 c_1 120 23", 1, "2"),
+            ("ct 0 c_1 2 12 0", 4, "12"),
+            ("ccd 2 40 6", 2, "40"),
+        ];
+        for (line, index, token) in cases {
+            let error = input_error(line);
+            assert_eq!(error.line, 3, "{}", line);
+            assert_eq!(
+                (error.index, error.token),
+                (index, Some(token.to_string())),
+                "{}",
+                line
+            );
         }
     }
 }
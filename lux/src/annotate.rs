@@ -18,7 +18,9 @@ impl Annotate {
     ///
     /// Action to perform
     pub fn circle(x: Coordinate, y: Coordinate) -> Action {
-        format!("{} {} {}", Commands::ANNOTATE_CIRCLE, x, y)
+        ActionKind::AnnotateCircle {
+            position: Position::new(x, y),
+        }
     }
 
     /// Returns annotate circle action
@@ -49,7 +51,9 @@ impl Annotate {
     ///
     /// Action to perform
     pub fn x(x: Coordinate, y: Coordinate) -> Action {
-        format!("{} {} {}", Commands::ANNOTATE_CROSS, x, y)
+        ActionKind::AnnotateCross {
+            position: Position::new(x, y),
+        }
     }
 
     /// Returns annotate cross action
@@ -82,7 +86,10 @@ impl Annotate {
     ///
     /// Action to perform
     pub fn line(x1: Coordinate, y1: Coordinate, x2: Coordinate, y2: Coordinate) -> Action {
-        format!("{} {} {} {} {}", Commands::ANNOTATE_LINE, x1, y1, x2, y2)
+        ActionKind::AnnotateLine {
+            from: Position::new(x1, y1),
+            to:   Position::new(x2, y2),
+        }
     }
 
     /// Returns annotate line action
@@ -115,14 +122,11 @@ impl Annotate {
     ///
     /// Action to perform
     pub fn text(x: Coordinate, y: Coordinate, message: &str, font_size: i32) -> Action {
-        format!(
-            "{} {} {} '{}' {}",
-            Commands::ANNOTATE_TEXT,
-            x,
-            y,
-            message,
-            font_size
-        )
+        ActionKind::AnnotateText {
+            position: Position::new(x, y),
+            message: message.to_string(),
+            font_size,
+        }
     }
 
     /// Annotate text command
@@ -179,6 +183,8 @@ impl Annotate {
     ///
     /// Action to perform
     pub fn sidetext(message: &str) -> Action {
-        format!("{} '{}'", Commands::ANNOTATE_SIDE_TEXT, message)
+        ActionKind::AnnotateSideText {
+            message: message.to_string(),
+        }
     }
 }
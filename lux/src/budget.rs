@@ -0,0 +1,235 @@
+use std::{fmt,
+          time::{Duration, Instant}};
+
+/// Time referee gives agent for one turn
+pub const DEFAULT_TURN_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Extra time referee gives agent for whole match, spent by turns exceeding
+/// [`DEFAULT_TURN_TIMEOUT`]
+pub const DEFAULT_OVERAGE_TIME: Duration = Duration::from_secs(60);
+
+/// Time reserved at the end of turn to write and flush actions
+pub const DEFAULT_TIME_MARGIN: Duration = Duration::from_millis(100);
+
+/// Time budget of current turn along with overage pool of whole match
+///
+/// Turn timer starts when turn observation is parsed, time spent above turn
+/// limit is charged to overage pool when turn ends
+///
+/// # Examples
+///
+/// ```no_run
+/// # use lux_ai_api::*;
+/// # let mut environment = Environment::new();
+/// environment.run_with_budget(TimeBudget::default(), &mut |agent, environment, budget| {
+///     while !budget.is_expired() {
+///         // improve actions
+///     }
+///     Ok(())
+/// })?;
+/// # Ok::<(), LuxAiError>(())
+/// ```
+#[derive(Clone, Copy, fmt::Debug)]
+pub struct TimeBudget {
+    turn_limit:    Duration,
+    overage_limit: Duration,
+    margin:        Duration,
+    turn_start:    Instant,
+    overage_used:  Duration,
+}
+
+impl Default for TimeBudget {
+    fn default() -> Self {
+        Self::new(
+            DEFAULT_TURN_TIMEOUT,
+            DEFAULT_OVERAGE_TIME,
+            DEFAULT_TIME_MARGIN,
+        )
+    }
+}
+
+impl TimeBudget {
+    /// Creates [`TimeBudget`] with turn timer started now
+    ///
+    /// # Parameters
+    ///
+    /// - `turn_limit` - time given for one turn
+    /// - `overage_limit` - extra time given for whole match
+    /// - `margin` - time reserved at the end of turn to send actions
+    ///
+    /// # Returns
+    ///
+    /// A new created [`TimeBudget`] with unused overage
+    pub fn new(turn_limit: Duration, overage_limit: Duration, margin: Duration) -> Self {
+        Self {
+            turn_limit,
+            overage_limit,
+            margin,
+            turn_start: Instant::now(),
+            overage_used: Duration::ZERO,
+        }
+    }
+
+    /// Starts timer of new turn
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - mutable Self reference
+    ///
+    /// # Returns
+    ///
+    /// Nothing
+    pub fn start_turn(&mut self) { self.turn_start = Instant::now(); }
+
+    /// Ends current turn and charges time above turn limit to overage
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - mutable Self reference
+    ///
+    /// # Returns
+    ///
+    /// Time spent above turn limit
+    pub fn end_turn(&mut self) -> Duration {
+        let overrun = self.elapsed().saturating_sub(self.turn_limit);
+        self.overage_used += overrun;
+        overrun
+    }
+
+    /// Returns time passed since turn started
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// `Duration` value
+    pub fn elapsed(&self) -> Duration { self.turn_start.elapsed() }
+
+    /// Returns moment turn handler should return by, turn limit minus margin
+    ///
+    /// Overage left is never added to deadline, this is conservative choice so
+    /// overage pool only absorbs unexpected overruns instead of being spent on
+    /// search by the first turns of match
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// `Instant` value
+    pub fn deadline(&self) -> Instant {
+        self.turn_start + self.turn_limit.saturating_sub(self.margin)
+    }
+
+    /// Returns time left until [`TimeBudget::deadline`]
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// `Duration` value, zero if deadline has passed
+    pub fn remaining(&self) -> Duration {
+        self.deadline().saturating_duration_since(Instant::now())
+    }
+
+    /// Whether or not deadline has passed, anytime algorithms should stop and
+    /// return best result found so far
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// `bool` value
+    pub fn is_expired(&self) -> bool { Instant::now() >= self.deadline() }
+
+    /// Returns overage spent by previous turns
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// `Duration` value
+    pub fn overage_used(&self) -> Duration { self.overage_used }
+
+    /// Returns overage left for the rest of match
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// `Duration` value
+    pub fn overage_remaining(&self) -> Duration {
+        self.overage_limit.saturating_sub(self.overage_used)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use super::*;
+
+    const HOUR: Duration = Duration::from_secs(3600);
+
+    #[test]
+    fn remaining_is_zero_after_deadline() {
+        let budget = TimeBudget::new(Duration::from_millis(20), HOUR, Duration::from_millis(10));
+        assert!(budget.remaining() <= Duration::from_millis(10));
+        thread::sleep(Duration::from_millis(15));
+        assert_eq!(budget.remaining(), Duration::ZERO);
+        assert!(budget.is_expired());
+    }
+
+    #[test]
+    fn deadline_ignores_overage() {
+        let budget = TimeBudget::new(Duration::from_secs(3), HOUR, Duration::from_secs(1));
+        assert!(budget.remaining() <= Duration::from_secs(2));
+        assert!(!budget.is_expired());
+    }
+
+    #[test]
+    fn turn_overrun_is_charged_to_overage() {
+        let mut budget = TimeBudget::new(Duration::ZERO, Duration::from_millis(30), Duration::ZERO);
+        thread::sleep(Duration::from_millis(10));
+        let overrun = budget.end_turn();
+        assert!(overrun >= Duration::from_millis(10));
+        assert_eq!(budget.overage_used(), overrun);
+        assert_eq!(
+            budget.overage_remaining(),
+            Duration::from_millis(30).saturating_sub(overrun)
+        );
+
+        thread::sleep(Duration::from_millis(30));
+        budget.end_turn();
+        assert_eq!(budget.overage_remaining(), Duration::ZERO);
+    }
+
+    #[test]
+    fn turn_within_limit_spends_no_overage() {
+        let mut budget = TimeBudget::new(HOUR, HOUR, Duration::ZERO);
+        assert_eq!(budget.end_turn(), Duration::ZERO);
+        assert_eq!(budget.overage_used(), Duration::ZERO);
+        assert_eq!(budget.overage_remaining(), HOUR);
+    }
+
+    #[test]
+    fn start_turn_restarts_timer() {
+        let mut budget = TimeBudget::new(Duration::from_millis(200), HOUR, Duration::ZERO);
+        thread::sleep(Duration::from_millis(250));
+        assert!(budget.is_expired());
+        budget.start_turn();
+        assert!(budget.elapsed() < Duration::from_millis(250));
+        assert!(!budget.is_expired());
+    }
+}
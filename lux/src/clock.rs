@@ -0,0 +1,210 @@
+use std::fmt;
+
+use crate::*;
+
+/// Day/night cycle calendar of match at given turn, driven by
+/// `GAME_CONSTANTS.parameters`
+///
+/// Turn is counted the same way as [`Agent::turn`], i.e. from 1, while referee
+/// counts turns from 0
+///
+/// # Examples
+///
+/// ```
+/// # use lux_ai_api::*;
+/// # let agent = MapGenerator::new(1, 12)?.generate().to_agent(0);
+/// let clock = agent.clock();
+/// if clock.is_day() && clock.turns_until_night() < 5 {
+///     // return to city
+/// }
+/// # Ok::<(), LuxAiError>(())
+/// ```
+///
+/// # See also
+///
+/// Check <https://www.lux-ai.org/specs-2021#Day/Night%20Cycle>
+#[derive(Clone, Copy, PartialEq, Eq, fmt::Debug)]
+pub struct GameClock {
+    turn: TurnAmount,
+}
+
+impl GameClock {
+    /// Creates [`GameClock`] at given turn
+    ///
+    /// # Parameters
+    ///
+    /// - `turn` - turn index, counted the same way as [`Agent::turn`]
+    ///
+    /// # Returns
+    ///
+    /// A new created [`GameClock`]
+    pub fn new(turn: TurnAmount) -> Self { Self { turn } }
+
+    /// Returns turn index, counted the same way as [`Agent::turn`]
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// Turn index
+    pub fn turn(&self) -> TurnAmount { self.turn }
+
+    /// Returns turn index counted from 0, as referee counts it
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// Turn index
+    pub fn step(&self) -> TurnAmount { self.turn - 1 }
+
+    /// Returns length of one day and night cycle
+    ///
+    /// # Parameters
+    ///
+    /// None
+    ///
+    /// # Returns
+    ///
+    /// Turns amount
+    pub fn cycle_length() -> TurnAmount {
+        GAME_CONSTANTS.parameters.day_length + GAME_CONSTANTS.parameters.night_length
+    }
+
+    /// Returns index of current day and night cycle, starting from 0
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// Cycle index
+    pub fn cycle_index(&self) -> DayAmount { self.step() / Self::cycle_length() }
+
+    /// Returns turn index inside current day and night cycle, starting from 0
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// Turn index
+    pub fn cycle_turn(&self) -> TurnAmount { self.step() % Self::cycle_length() }
+
+    /// Whether or not current turn is resolved at day
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// `bool` value
+    pub fn is_day(&self) -> bool { self.cycle_turn() < GAME_CONSTANTS.parameters.day_length }
+
+    /// Whether or not current turn is resolved at night
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// `bool` value
+    pub fn is_night(&self) -> bool { !self.is_day() }
+
+    /// Returns count of day turns left before night starts
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// Turns amount, `0` at night
+    pub fn turns_until_night(&self) -> TurnAmount {
+        (GAME_CONSTANTS.parameters.day_length - self.cycle_turn()).max(0)
+    }
+
+    /// Returns count of night turns left before day starts
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// Turns amount, `0` at day
+    pub fn turns_until_dawn(&self) -> TurnAmount {
+        match self.is_night() {
+            true => Self::cycle_length() - self.cycle_turn(),
+            false => 0,
+        }
+    }
+
+    /// Returns count of night turns left until the end of next night,
+    /// including current turn if it is night
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// Turns amount, the rest of current night or whole next night
+    pub fn night_turns_left(&self) -> TurnAmount {
+        match self.is_night() {
+            true => self.turns_until_dawn(),
+            false => GAME_CONSTANTS.parameters.night_length,
+        }
+    }
+
+    /// Returns count of nights not finished yet until `max_days`, including
+    /// current one
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// Nights amount
+    pub fn nights_remaining(&self) -> DayAmount {
+        let parameters = &GAME_CONSTANTS.parameters;
+        if parameters.max_days <= parameters.day_length {
+            return 0;
+        }
+        let last_night = (parameters.max_days - parameters.day_length - 1) / Self::cycle_length();
+        (last_night - self.cycle_index() + 1).max(0)
+    }
+
+    /// Returns count of turns left until `max_days`, including current turn
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// Turns amount
+    pub fn turns_remaining(&self) -> TurnAmount {
+        (GAME_CONSTANTS.parameters.max_days - self.step()).max(0)
+    }
+
+    /// Whether or not all turns of match are played
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// `bool` value
+    pub fn is_game_over(&self) -> bool { self.turns_remaining() == 0 }
+}
@@ -0,0 +1,245 @@
+use std::{collections::HashSet, fmt};
+
+use crate::*;
+
+/// Group of connected [`Cells`][Cell] with the same [`ResourceType`]
+///
+/// # Examples
+///
+/// ```
+/// # use lux_ai_api::*;
+/// # let agent = MapGenerator::new(1, 12)?.generate().to_agent(0);
+/// let clusters = ResourceCluster::find_all(&agent.game_map, &agent.players);
+/// let target = clusters
+///     .iter()
+///     .filter(|cluster| agent.player().is_researched(cluster.resource_type))
+///     .max_by_key(|cluster| cluster.total_amount);
+/// # Ok::<(), LuxAiError>(())
+/// ```
+///
+/// # See also
+///
+/// Check <https://www.lux-ai.org/specs-2021#Resources>
+#[derive(Clone, PartialEq, fmt::Debug)]
+pub struct ResourceCluster {
+    /// Type of resource of all cells in cluster
+    pub resource_type: ResourceType,
+
+    /// Positions of cells in cluster
+    pub cells: Vec<Position>,
+
+    /// Sum of resource amounts of all cells in cluster
+    pub total_amount: ResourceAmount,
+
+    /// Mean `(x, y)` of cells in cluster
+    pub centroid: (f32, f32),
+
+    /// Empty cells next to cluster, where city tile can be built: no resource
+    /// and no city tile
+    pub perimeter: Vec<Position>,
+
+    /// Manhattan distance from nearest city tile of each team to cluster,
+    /// indexed by team id, `None` if team has no city tiles
+    pub city_distances: Vec<Option<f32>>,
+}
+
+impl ResourceCluster {
+    /// Finds all resource clusters on [`GameMap`]
+    ///
+    /// # Parameters
+    ///
+    /// - `game_map` - [`GameMap`] to search clusters on
+    /// - `players` - all [`players`][Player], whose cities distances are
+    ///   measured
+    ///
+    /// # Returns
+    ///
+    /// Clusters in order of their first cell, row by row
+    pub fn find_all(game_map: &GameMap, players: &[Player]) -> Vec<Self> {
+        let city_tiles: Vec<Vec<Position>> = players
+            .iter()
+            .map(|player| {
+                player
+                    .cities
+                    .values()
+                    .flat_map(|city| city.city_tiles(game_map))
+                    .map(|city_tile| city_tile.pos)
+                    .collect()
+            })
+            .collect();
+
+        let mut visited = HashSet::new();
+        let mut clusters = vec![];
+        for cell in game_map.map.iter().flatten() {
+            if visited.contains(&cell.pos) {
+                continue;
+            }
+            if let Some(resource) = cell.resource.as_ref().filter(|_| cell.has_resource()) {
+                let cells = Self::flood_fill(game_map, cell.pos, resource.resource_type);
+                visited.extend(cells.iter().copied());
+                clusters.push(Self::new(
+                    game_map,
+                    resource.resource_type,
+                    cells,
+                    &city_tiles,
+                ));
+            }
+        }
+        clusters
+    }
+
+    /// Returns [`Position`] of cell nearest to centroid
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// Rounded centroid [`Position`]
+    pub fn centroid_position(&self) -> Position {
+        Position::new(
+            self.centroid.0.round() as Coordinate,
+            self.centroid.1.round() as Coordinate,
+        )
+    }
+
+    fn new(
+        game_map: &GameMap, resource_type: ResourceType, cells: Vec<Position>,
+        city_tiles: &[Vec<Position>],
+    ) -> Self {
+        let total_amount = cells
+            .iter()
+            .filter_map(|position| game_map[*position].resource.as_ref())
+            .map(|resource| resource.amount)
+            .sum();
+        let count = cells.len() as f32;
+        let centroid = (
+            cells.iter().map(|position| position.x as f32).sum::<f32>() / count,
+            cells.iter().map(|position| position.y as f32).sum::<f32>() / count,
+        );
+
+        let mut perimeter = vec![];
+        let mut seen = HashSet::new();
+        for position in cells.iter() {
+            for neighbor in game_map.neighbors(*position) {
+                let cell = &game_map[neighbor];
+                if !cell.has_resource() && cell.citytile.is_none() && seen.insert(neighbor) {
+                    perimeter.push(neighbor);
+                }
+            }
+        }
+
+        let city_distances = city_tiles
+            .iter()
+            .map(|positions| {
+                positions
+                    .iter()
+                    .flat_map(|city_tile| cells.iter().map(|cell| city_tile.distance_to(cell)))
+                    .min_by(|a, b| a.total_cmp(b))
+            })
+            .collect();
+
+        Self {
+            resource_type,
+            cells,
+            total_amount,
+            centroid,
+            perimeter,
+            city_distances,
+        }
+    }
+
+    fn flood_fill(
+        game_map: &GameMap, start: Position, resource_type: ResourceType,
+    ) -> Vec<Position> {
+        let mut cells = vec![start];
+        let mut seen = HashSet::from([start]);
+        let mut index = 0;
+        while index < cells.len() {
+            for neighbor in game_map.neighbors(cells[index]) {
+                let same_type = game_map[neighbor].has_resource() &&
+                    game_map[neighbor]
+                        .resource
+                        .as_ref()
+                        .is_some_and(|resource| resource.resource_type == resource_type);
+                if same_type && seen.insert(neighbor) {
+                    cells.push(neighbor);
+                }
+            }
+            index += 1;
+        }
+        cells
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add_resource(
+        game_map: &mut GameMap, resource_type: ResourceType, amount: i32, x: i32, y: i32,
+    ) {
+        game_map[Position::new(x, y)].resource = Some(Resource::new(resource_type, amount));
+    }
+
+    fn positions(positions: &[(i32, i32)]) -> HashSet<Position> {
+        positions
+            .iter()
+            .map(|&(x, y)| Position::new(x, y))
+            .collect()
+    }
+
+    #[test]
+    fn clusters_are_split_by_resource_type_and_connectivity() {
+        let mut game_map = GameMap::new(6, 6);
+        for (x, y) in [(0, 0), (1, 0), (0, 1)] {
+            add_resource(&mut game_map, ResourceType::Wood, 100, x, y);
+        }
+        add_resource(&mut game_map, ResourceType::Coal, 50, 1, 1);
+        add_resource(&mut game_map, ResourceType::Wood, 0, 5, 0);
+        add_resource(&mut game_map, ResourceType::Wood, 200, 4, 4);
+        let mut players = vec![Player::new(0), Player::new(1)];
+        let mut city = City::new(0, "c_1".to_string(), 0.0, 0.0);
+        city.add_city_tile(&mut game_map, Position::new(2, 0), 0.0);
+        players[0].cities.insert(city.cityid.clone(), city);
+
+        let clusters = ResourceCluster::find_all(&game_map, &players);
+
+        assert_eq!(clusters.len(), 3);
+        let wood = &clusters[0];
+        assert_eq!(wood.resource_type, ResourceType::Wood);
+        assert_eq!(
+            wood.cells.iter().copied().collect::<HashSet<_>>(),
+            positions(&[(0, 0), (1, 0), (0, 1)])
+        );
+        assert_eq!(wood.total_amount, 300);
+        assert_eq!(wood.centroid, (1.0 / 3.0, 1.0 / 3.0));
+        assert_eq!(wood.centroid_position(), Position::new(0, 0));
+        assert_eq!(
+            wood.perimeter.iter().copied().collect::<HashSet<_>>(),
+            positions(&[(0, 2)])
+        );
+        assert_eq!(wood.city_distances, [Some(1.0), None]);
+
+        let coal = &clusters[1];
+        assert_eq!(coal.resource_type, ResourceType::Coal);
+        assert_eq!(coal.cells, [Position::new(1, 1)]);
+        assert_eq!(
+            coal.perimeter.iter().copied().collect::<HashSet<_>>(),
+            positions(&[(2, 1), (1, 2)])
+        );
+
+        let far_wood = &clusters[2];
+        assert_eq!(far_wood.cells, [Position::new(4, 4)]);
+        assert_eq!(far_wood.total_amount, 200);
+        assert_eq!(far_wood.perimeter.len(), 4);
+        assert_eq!(far_wood.city_distances, [Some(6.0), None]);
+    }
+
+    #[test]
+    fn map_without_resources_has_no_clusters() {
+        let players = vec![Player::new(0), Player::new(1)];
+        assert!(ResourceCluster::find_all(&GameMap::new(4, 4), &players).is_empty());
+    }
+}
@@ -58,7 +58,7 @@ impl Commands {
 pub type CommandArgument = String;
 
 /// Represents input command from Lux AI API environment
-#[derive(fmt::Debug, PartialEq, Eq)]
+#[derive(Clone, fmt::Debug, PartialEq, Eq)]
 pub struct Command {
     /// Command arguments
     pub arguments: Vec<CommandArgument>,
@@ -95,9 +95,11 @@ impl Command {
     ///
     /// Argument converted to `T` type at position `argument_idx` or error
     pub fn argument<T: str::FromStr>(&self, argument_idx: usize) -> LuxAiResult<T> {
-        let argument = self.arguments[argument_idx]
-            .parse::<T>()
-            .map_err(|_| LuxAiError::CommandFormat(self.arguments.clone()))?;
+        let argument = self
+            .arguments
+            .get(argument_idx)
+            .and_then(|argument| argument.parse::<T>().ok())
+            .ok_or_else(|| LuxAiError::CommandFormat(self.arguments.clone()))?;
         Ok(argument)
     }
 
@@ -120,3 +122,456 @@ impl Command {
         }
     }
 }
+
+/// Malformed line of Lux AI API input
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// # use lux_ai_api::*;
+/// # let input = "0\n12 12\nr wood 40 40 100\nD_DONE\n";
+/// # let mut environment = Environment::with_io(io::Cursor::new(input), Vec::new());
+/// # let mut agent = Agent::new(&mut environment)?;
+/// match agent.update_turn(&mut environment) {
+///     Err(LuxAiError::InputFormat(error)) => eprintln!("line {}: {}", error.line, error),
+///     result => result?,
+/// }
+/// # Ok::<(), LuxAiError>(())
+/// ```
+#[derive(Clone, PartialEq, Eq, fmt::Debug)]
+pub struct InputFormatError {
+    /// Number of line in input, starting from `1`
+    pub line: usize,
+
+    /// Turn index line was read at, `0` for lines read before first turn
+    pub turn: TurnAmount,
+
+    /// Expected count of tokens, including command type
+    pub arity: usize,
+
+    /// Index of offending token
+    pub index: usize,
+
+    /// Offending token, `None` if line is shorter than `arity`
+    pub token: Option<CommandArgument>,
+
+    /// All tokens of line
+    pub tokens: Vec<CommandArgument>,
+}
+
+impl fmt::Display for InputFormatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}, turn {}: expected {} tokens in {:?}, ",
+            self.line, self.turn, self.arity, self.tokens
+        )?;
+        match &self.token {
+            Some(token) if self.index >= self.arity =>
+                write!(f, "unexpected token {} `{}`", self.index, token),
+            Some(token) => write!(f, "invalid token {} `{}`", self.index, token),
+            None => write!(f, "missing token {}", self.index),
+        }
+    }
+}
+
+impl std::error::Error for InputFormatError {}
+
+/// Tokens of input line along with its location, to report
+/// [`InputFormatError`]
+#[derive(fmt::Debug)]
+pub(crate) struct InputLine<'a> {
+    tokens: &'a [CommandArgument],
+    line:   usize,
+    turn:   TurnAmount,
+    arity:  usize,
+}
+
+impl<'a> InputLine<'a> {
+    /// Creates [`InputLine`] validating it has exactly `arity` tokens
+    ///
+    /// # Parameters
+    ///
+    /// - `command` - [`Command`] read from line
+    /// - `line` - number of line in input
+    /// - `turn` - turn index line was read at
+    /// - `arity` - expected count of tokens, including command type
+    ///
+    /// # Returns
+    ///
+    /// A new created [`InputLine`] or error pointing to first missing or extra
+    /// token
+    pub(crate) fn new(
+        command: &'a Command, line: usize, turn: TurnAmount, arity: usize,
+    ) -> Result<Self, InputFormatError> {
+        let input_line = Self {
+            tokens: &command.arguments,
+            line,
+            turn,
+            arity,
+        };
+        match input_line.tokens.len() == arity {
+            true => Ok(input_line),
+            false => Err(input_line.error(input_line.tokens.len().min(arity))),
+        }
+    }
+
+    /// Creates [`InputLine`] of input command defined by protocol, validating
+    /// it has as many tokens as [`InputMessage::arity`] expects
+    ///
+    /// # Parameters
+    ///
+    /// - `command` - [`Command`] read from line
+    /// - `line` - number of line in input
+    /// - `turn` - turn index line was read at
+    ///
+    /// # Returns
+    ///
+    /// A new created [`InputLine`], `None` if command is not defined by
+    /// protocol, or error pointing to first missing or extra token
+    pub(crate) fn from_command(
+        command: &'a Command, line: usize, turn: TurnAmount,
+    ) -> Result<Option<Self>, InputFormatError> {
+        let arity = match command.arguments.first() {
+            Some(command_type) => match InputMessage::arity(command_type) {
+                Some(arity) => arity,
+                None => return Ok(None),
+            },
+            None => 1,
+        };
+        Self::new(command, line, turn, arity).map(Some)
+    }
+
+    /// Returns token converting into type `T` at position `index`
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    /// - `index` - index of token (0 - for command type, 1 .. inf - for other)
+    ///
+    /// # Type parameters
+    ///
+    /// - `T` - type to convert token to
+    ///
+    /// # Returns
+    ///
+    /// Token converted to `T` type or error
+    pub(crate) fn token<T: str::FromStr>(&self, index: usize) -> Result<T, InputFormatError> {
+        self.tokens
+            .get(index)
+            .and_then(|token| token.parse().ok())
+            .ok_or_else(|| self.error(index))
+    }
+
+    /// Returns [`Position`] from two tokens starting at `index`
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    /// - `index` - index of X coordinate token
+    ///
+    /// # Returns
+    ///
+    /// [`Position`] or error
+    pub(crate) fn position(&self, index: usize) -> Result<Position, InputFormatError> {
+        Ok(Position::new(
+            self.token::<Coordinate>(index)?,
+            self.token::<Coordinate>(index + 1)?,
+        ))
+    }
+
+    /// Returns [`Position`] error pointing to its first coordinate token
+    /// outside of [`GameMap`]
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    /// - `index` - index of X coordinate token
+    /// - `position` - [`Position`] read from line
+    /// - `width` - width of [`GameMap`] position should be inside of
+    ///
+    /// # Returns
+    ///
+    /// [`InputFormatError`] pointing to X or Y coordinate token
+    pub(crate) fn position_error(
+        &self, index: usize, position: Position, width: Coordinate,
+    ) -> InputFormatError {
+        match (0..width).contains(&position.x) {
+            true => self.error(index + 1),
+            false => self.error(index),
+        }
+    }
+
+    /// Returns error pointing to token at position `index`
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    /// - `index` - index of offending token
+    ///
+    /// # Returns
+    ///
+    /// [`InputFormatError`] with location of line
+    pub(crate) fn error(&self, index: usize) -> InputFormatError {
+        InputFormatError {
+            line: self.line,
+            turn: self.turn,
+            arity: self.arity,
+            index,
+            token: self.tokens.get(index).cloned(),
+            tokens: self.tokens.to_vec(),
+        }
+    }
+}
+
+/// Typed line of Lux AI API turn input
+#[derive(Clone, PartialEq, fmt::Debug)]
+pub enum InputMessage {
+    /// Researched points of team, [`Commands::RESEARCH_POINTS`]
+    ResearchPoints {
+        /// Team id
+        team:            TeamId,
+        /// Researched points
+        research_points: ResearchPointAmount,
+    },
+
+    /// Resource on tile, [`Commands::RESOURCES`]
+    Resource {
+        /// Type of resource
+        resource_type: ResourceType,
+        /// [`Position`] of tile
+        position:      Position,
+        /// Amount of resource
+        amount:        ResourceAmount,
+    },
+
+    /// Unit on tile, [`Commands::UNITS`]
+    Unit {
+        /// Type of unit
+        unit_type: UnitType,
+        /// Team id
+        team:      TeamId,
+        /// Unit id
+        unit_id:   EntityId,
+        /// [`Position`] of unit
+        position:  Position,
+        /// Turns to next action
+        cooldown:  Cooldown,
+        /// Resources carried by unit
+        cargo:     Cargo,
+    },
+
+    /// City of team, [`Commands::CITY`]
+    City {
+        /// Team id
+        team:         TeamId,
+        /// City id
+        city_id:      EntityId,
+        /// Fuel amount of city
+        fuel:         FuelAmount,
+        /// Fuel burnt every night turn
+        light_upkeep: FuelAmount,
+    },
+
+    /// City tile of city, [`Commands::CITY_TILES`]
+    CityTile {
+        /// Team id
+        team:     TeamId,
+        /// City id
+        city_id:  EntityId,
+        /// [`Position`] of city tile
+        position: Position,
+        /// Turns to next action
+        cooldown: Cooldown,
+    },
+
+    /// Road on tile, [`Commands::ROADS`]
+    Road {
+        /// [`Position`] of tile
+        position: Position,
+        /// Road development progress
+        road:     RoadAmount,
+    },
+
+    /// End of turn input, [`Commands::DONE`]
+    Done,
+
+    /// Command not defined by protocol, kept as is
+    Unknown(Command),
+}
+
+impl InputMessage {
+    /// Returns expected count of tokens of input command, including command
+    /// type
+    ///
+    /// # Parameters
+    ///
+    /// - `command_type` - first token of line
+    ///
+    /// # Returns
+    ///
+    /// Count of tokens or `None` if command is not defined by protocol
+    pub fn arity(command_type: &str) -> Option<usize> {
+        let arity = match command_type {
+            Commands::RESEARCH_POINTS => 3,
+            Commands::RESOURCES => 5,
+            Commands::UNITS => 7 + ResourceType::VALUES.len(),
+            Commands::CITY => 5,
+            Commands::CITY_TILES => 6,
+            Commands::ROADS => 4,
+            Commands::DONE => 1,
+            _ => return None,
+        };
+        Some(arity)
+    }
+
+    /// Parses [`InputMessage`] from [`Command`]
+    ///
+    /// # Parameters
+    ///
+    /// - `command` - [`Command`] read from input
+    /// - `line` - number of line in input, reported in errors
+    /// - `turn` - turn index line was read at, reported in errors
+    ///
+    /// # Returns
+    ///
+    /// Parsed [`InputMessage`] or [`LuxAiError::InputFormat`] error
+    pub fn parse(command: Command, line: usize, turn: TurnAmount) -> LuxAiResult<Self> {
+        match InputLine::from_command(&command, line, turn)? {
+            Some(input) => Ok(Self::from_input(&input)?),
+            None => Ok(Self::Unknown(command)),
+        }
+    }
+
+    /// Parses [`InputMessage`] from [`InputLine`] of known arity
+    ///
+    /// # Parameters
+    ///
+    /// - `input` - [`InputLine`] created by [`InputLine::from_command`]
+    ///
+    /// # Returns
+    ///
+    /// Parsed [`InputMessage`] or error pointing to offending token
+    pub(crate) fn from_input(input: &InputLine) -> Result<Self, InputFormatError> {
+        let message = match input.token::<String>(0)?.as_str() {
+            Commands::RESEARCH_POINTS => Self::ResearchPoints {
+                team:            input.token(1)?,
+                research_points: input.token(2)?,
+            },
+            Commands::RESOURCES => Self::Resource {
+                resource_type: input.token(1)?,
+                position:      input.position(2)?,
+                amount:        input.token(4)?,
+            },
+            Commands::UNITS => {
+                let argument_offset = 7;
+                let mut cargo = Cargo::default();
+                for (index, resource_type) in ResourceType::VALUES.iter().enumerate() {
+                    cargo[*resource_type] = input.token(argument_offset + index)?;
+                }
+                Self::Unit {
+                    unit_type: input.token(1)?,
+                    team: input.token(2)?,
+                    unit_id: input.token(3)?,
+                    position: input.position(4)?,
+                    cooldown: input.token(6)?,
+                    cargo,
+                }
+            },
+            Commands::CITY => Self::City {
+                team:         input.token(1)?,
+                city_id:      input.token(2)?,
+                fuel:         input.token(3)?,
+                light_upkeep: input.token(4)?,
+            },
+            Commands::CITY_TILES => Self::CityTile {
+                team:     input.token(1)?,
+                city_id:  input.token(2)?,
+                position: input.position(3)?,
+                cooldown: input.token(5)?,
+            },
+            Commands::ROADS => Self::Road {
+                position: input.position(1)?,
+                road:     input.token(3)?,
+            },
+            Commands::DONE => Self::Done,
+            _ => Self::Unknown(Command {
+                arguments: input.tokens.to_vec(),
+            }),
+        };
+        Ok(message)
+    }
+}
+
+/// What to do with input commands not defined by protocol and having no
+/// handler registered with [`Environment::register_command_handler`]
+#[derive(Clone, Copy, PartialEq, Eq, fmt::Debug)]
+pub enum UnknownCommandPolicy {
+    /// Skip command silently
+    Ignore,
+
+    /// Skip command and report it to stderr
+    Warn,
+
+    /// Keep command in [`Agent::unknown_commands`] until next turn
+    Collect,
+
+    /// Stop reading turn with [`LuxAiError::UnknownCommand`] error
+    Fail,
+}
+
+/// Ignore unknown commands by default, as referee may add new ones
+impl Default for UnknownCommandPolicy {
+    fn default() -> Self { Self::Ignore }
+}
+
+/// Handler of input command not defined by protocol, called with [`Agent`]
+/// being updated and command read
+pub type CommandHandler = Box<dyn FnMut(&mut Agent, &Command) -> LuxAiResult>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(line: &str) -> Command { Command::new(line.to_string()) }
+
+    #[test]
+    fn known_commands_are_parsed_into_messages() {
+        assert_eq!(
+            InputMessage::parse(command("ct 1 c_2 3 4 0.5"), 7, 2).unwrap(),
+            InputMessage::CityTile {
+                team:     1,
+                city_id:  "c_2".to_string(),
+                position: Position::new(3, 4),
+                cooldown: 0.5,
+            }
+        );
+        assert_eq!(
+            InputMessage::parse(command("D_DONE"), 8, 2).unwrap(),
+            InputMessage::Done
+        );
+    }
+
+    #[test]
+    fn unknown_command_is_kept_as_is() {
+        let unknown = command("x 1 2");
+        assert_eq!(
+            InputMessage::parse(unknown.clone(), 3, 1).unwrap(),
+            InputMessage::Unknown(unknown)
+        );
+    }
+
+    #[test]
+    fn malformed_line_keeps_its_location() {
+        let error = InputLine::from_command(&command("ccd 1 2"), 5, 4).unwrap_err();
+        assert_eq!((error.line, error.turn, error.arity), (5, 4, 4));
+        assert_eq!((error.index, error.token), (3, None));
+
+        let command = command("ccd 1 y 0.5");
+        let input = InputLine::from_command(&command, 6, 4).unwrap().unwrap();
+        let error = InputMessage::from_input(&input).unwrap_err();
+        assert_eq!((error.line, error.index), (6, 2));
+        assert_eq!(error.token, Some("y".to_string()));
+    }
+}
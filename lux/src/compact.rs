@@ -0,0 +1,599 @@
+use std::{collections::HashMap, fmt, sync::Arc};
+
+use crate::*;
+
+/// Max width and height of map supported by [`CompactState`]
+pub const COMPACT_GRID_SIZE: Coordinate = 32;
+
+/// Index of interned [`EntityId`] in [`CompactState`]
+pub type EntityIndex = u16;
+
+/// One bit per cell of [`COMPACT_GRID_SIZE`] x [`COMPACT_GRID_SIZE`] grid,
+/// stored inline so copying it never allocates
+#[derive(Clone, Copy, Default, PartialEq, Eq, fmt::Debug)]
+pub struct BitGrid {
+    words: [u64; 16],
+}
+
+impl BitGrid {
+    /// Returns bit of cell at `position`
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    /// - `position` - [`Position`] of cell
+    ///
+    /// # Returns
+    ///
+    /// `bool` value, `false` if `position` is outside of grid
+    pub fn get(&self, position: Position) -> bool {
+        match Self::bit(position) {
+            Some(bit) => self.words[bit / 64] & (1 << (bit % 64)) != 0,
+            None => false,
+        }
+    }
+
+    /// Sets bit of cell at `position`, positions outside of grid are ignored
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - mutable Self reference
+    /// - `position` - [`Position`] of cell
+    /// - `value` - new bit value
+    ///
+    /// # Returns
+    ///
+    /// Nothing
+    pub fn set(&mut self, position: Position, value: bool) {
+        if let Some(bit) = Self::bit(position) {
+            match value {
+                true => self.words[bit / 64] |= 1 << (bit % 64),
+                false => self.words[bit / 64] &= !(1 << (bit % 64)),
+            }
+        }
+    }
+
+    /// Returns count of set bits
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// Cells count
+    pub fn count(&self) -> u32 { self.words.iter().map(|word| word.count_ones()).sum() }
+
+    /// Whether or not no bit is set
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// `bool` value
+    pub fn is_empty(&self) -> bool { self.words.iter().all(|word| *word == 0) }
+
+    fn bit(position: Position) -> Option<usize> {
+        let size = COMPACT_GRID_SIZE;
+        let inside = position.x >= 0 && position.y >= 0 && position.x < size && position.y < size;
+        inside.then(|| (position.y * size + position.x) as usize)
+    }
+}
+
+/// [`Cell`] of [`CompactState`], city tile is stored inline
+#[derive(Clone, Copy, Default, PartialEq, fmt::Debug)]
+pub struct CompactCell {
+    /// Type of [`Resource`] on cell
+    pub resource_type: Option<ResourceType>,
+
+    /// Amount of [`Resource`] on cell
+    pub resource_amount: ResourceAmount,
+
+    /// Road development progress of cell
+    pub road: RoadAmount,
+
+    /// Interned id of [`City`] whose [`CityTile`] is on cell
+    pub city: Option<EntityIndex>,
+
+    /// Cooldown of [`CityTile`] on cell
+    pub city_tile_cooldown: Cooldown,
+}
+
+impl CompactCell {
+    /// Whether or not cell has a non-depleted resource
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// `bool` value
+    pub fn has_resource(&self) -> bool { self.resource_type.is_some() && self.resource_amount > 0 }
+}
+
+/// [`Unit`] of [`CompactState`] with interned id
+#[derive(Clone, Copy, PartialEq, fmt::Debug)]
+pub struct CompactUnit {
+    /// Interned id of unit
+    pub id: EntityIndex,
+
+    /// Team id of unit
+    pub team: TeamId,
+
+    /// Type of unit
+    pub unit_type: UnitType,
+
+    /// [`Position`] of unit
+    pub pos: Position,
+
+    /// Amount of turns to next action
+    pub cooldown: Cooldown,
+
+    /// Resources carried by unit
+    pub cargo: Cargo,
+}
+
+/// [`City`] of [`CompactState`] with interned id, its tiles are stored in
+/// [`CompactCell`]s
+#[derive(Clone, Copy, PartialEq, fmt::Debug)]
+pub struct CompactCity {
+    /// Interned id of city
+    pub id: EntityIndex,
+
+    /// Team id of city
+    pub team: TeamId,
+
+    /// Fuel amount of city
+    pub fuel: FuelAmount,
+
+    /// Fuel burnt by city every night turn
+    pub light_upkeep: FuelAmount,
+}
+
+/// Compact game state for tree search
+///
+/// Cells are stored in flat row-major array, entity ids are interned into
+/// [`EntityIndex`] and shared between clones, occupancy of units and city tiles
+/// is bit-packed per team. Cloning copies a few flat arrays without per-entity
+/// allocations, so its cost grows with map size and entity count only
+///
+/// # Examples
+///
+/// ```
+/// # use lux_ai_api::*;
+/// # let agent = MapGenerator::new(1, 12)?.generate().to_agent(0);
+/// # let rollouts = 8;
+/// let root = CompactState::from_agent(&agent)?;
+/// for _ in 0..rollouts {
+///     let mut state = root.clone();
+///     // play rollout on state
+/// }
+/// let agent = root.to_agent();
+/// # Ok::<(), LuxAiError>(())
+/// ```
+#[derive(Clone, PartialEq, fmt::Debug)]
+pub struct CompactState {
+    /// Team id of [`Agent`] whose state it is
+    pub team: TeamId,
+
+    /// Turn index, counted the same way as [`Agent::turn`]
+    pub turn: TurnAmount,
+
+    /// Width of map
+    pub width: Coordinate,
+
+    /// Height of map
+    pub height: Coordinate,
+
+    /// Research points of each team, indexed by team id
+    pub research_points: [ResearchPointAmount; TEAM_COUNT as usize],
+
+    /// Cells of map in row-major order
+    pub cells: Vec<CompactCell>,
+
+    /// Units of both teams
+    pub units: Vec<CompactUnit>,
+
+    /// Cities of both teams
+    pub cities: Vec<CompactCity>,
+
+    /// Cells with at least one unit, indexed by team id
+    pub unit_occupancy: [BitGrid; TEAM_COUNT as usize],
+
+    /// Cells with city tile, indexed by team id
+    pub city_tile_occupancy: [BitGrid; TEAM_COUNT as usize],
+
+    ids: Arc<Vec<EntityId>>,
+}
+
+impl CompactState {
+    /// Creates [`CompactState`] from [`Agent`] state
+    ///
+    /// # Parameters
+    ///
+    /// - `agent` - [`Agent`] reference
+    ///
+    /// # Returns
+    ///
+    /// A new created [`CompactState`] or error if map is larger than
+    /// [`COMPACT_GRID_SIZE`]
+    pub fn from_agent(agent: &Agent) -> LuxAiResult<Self> {
+        let game_map = &agent.game_map;
+        for size in [game_map.width, game_map.height] {
+            if size > COMPACT_GRID_SIZE {
+                return Err(LuxAiError::UnsupportedMapSize(size));
+            }
+        }
+
+        let mut ids = vec![];
+        let mut interned = HashMap::new();
+        let mut intern = |id: &EntityId| -> EntityIndex {
+            *interned.entry(id.clone()).or_insert_with(|| {
+                ids.push(id.clone());
+                (ids.len() - 1) as EntityIndex
+            })
+        };
+
+        let mut state = Self {
+            team:                agent.team,
+            turn:                agent.turn,
+            width:               game_map.width,
+            height:              game_map.height,
+            research_points:     [0; TEAM_COUNT as usize],
+            cells:               vec![
+                CompactCell::default();
+                (game_map.width * game_map.height) as usize
+            ],
+            units:               vec![],
+            cities:              vec![],
+            unit_occupancy:      Default::default(),
+            city_tile_occupancy: Default::default(),
+            ids:                 Arc::default(),
+        };
+        for cell in game_map.map.iter().flatten() {
+            let compact = state.cell_mut(cell.pos);
+            compact.road = cell.road;
+            if let Some(resource) = cell.resource.as_ref() {
+                compact.resource_type = Some(resource.resource_type);
+                compact.resource_amount = resource.amount;
+            }
+        }
+        for player in agent.players.iter() {
+            state.research_points[player.team as usize] = player.research_points;
+            for unit in player.units.iter() {
+                state.units.push(CompactUnit {
+                    id:        intern(&unit.id),
+                    team:      unit.team,
+                    unit_type: unit.unit_type,
+                    pos:       unit.pos,
+                    cooldown:  unit.cooldown,
+                    cargo:     unit.cargo,
+                });
+            }
+            let mut cities: Vec<&City> = player.cities.values().collect();
+            cities.sort_by(|a, b| a.cityid.cmp(&b.cityid));
+            for city in cities {
+                let id = intern(&city.cityid);
+                state.cities.push(CompactCity {
+                    id,
+                    team: city.teamid,
+                    fuel: city.fuel,
+                    light_upkeep: city.light_upkeep,
+                });
+                for city_tile in city.city_tiles(game_map) {
+                    let cell = state.cell_mut(city_tile.pos);
+                    cell.city = Some(id);
+                    cell.city_tile_cooldown = city_tile.cooldown;
+                }
+            }
+        }
+        state.ids = Arc::new(ids);
+        state.rebuild_occupancy();
+        Ok(state)
+    }
+
+    /// Converts state into [`Agent`]
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// A new created [`Agent`] with empty [`WorldModel`], city tiles of each
+    /// city are ordered row by row
+    pub fn to_agent(&self) -> Agent {
+        let mut game_map = GameMap::new(self.width, self.height);
+        let mut players: Vec<Player> = (0..TEAM_COUNT).map(Player::new).collect();
+        for (player, research_points) in players.iter_mut().zip(self.research_points) {
+            player.research_points = research_points;
+        }
+        for unit in self.units.iter() {
+            let id = self.entity_id(unit.id).to_string();
+            let mut converted = Unit::new(unit.team, unit.unit_type, id, unit.pos, unit.cooldown);
+            converted.cargo = unit.cargo;
+            players[unit.team as usize].units.push(converted);
+        }
+        for city in self.cities.iter() {
+            let id = self.entity_id(city.id).to_string();
+            let converted = City::new(city.team, id.clone(), city.fuel, city.light_upkeep);
+            players[city.team as usize].cities.insert(id, converted);
+        }
+
+        for (index, cell) in self.cells.iter().enumerate() {
+            let position = self.position(index);
+            game_map[position].road = cell.road;
+            if let Some(resource_type) = cell.resource_type {
+                game_map[position].resource =
+                    Some(Resource::new(resource_type, cell.resource_amount));
+            }
+            if let Some(city) = cell.city.and_then(|id| self.city(id)) {
+                let player = &mut players[city.team as usize];
+                if let Some(converted) = player.cities.get_mut(self.entity_id(city.id)) {
+                    converted.add_city_tile(&mut game_map, position, cell.city_tile_cooldown);
+                    player.city_tile_count += 1;
+                }
+            }
+        }
+
+        let unit_index = UnitIndex::from_players(&players);
+        Agent {
+            team: self.team,
+            turn: self.turn,
+            game_map,
+            players,
+            world: WorldModel::new(),
+            unit_index,
+            unknown_commands: vec![],
+        }
+    }
+
+    /// Returns cell at `position`
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    /// - `position` - [`Position`] of cell, inside of map
+    ///
+    /// # Returns
+    ///
+    /// [`CompactCell`] reference
+    pub fn cell(&self, position: Position) -> &CompactCell { &self.cells[self.index(position)] }
+
+    /// Returns mutable cell at `position`
+    ///
+    /// Occupancy is not updated, call [`CompactState::rebuild_occupancy`] after
+    /// changing city tiles
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - mutable Self reference
+    /// - `position` - [`Position`] of cell, inside of map
+    ///
+    /// # Returns
+    ///
+    /// Mutable [`CompactCell`] reference
+    pub fn cell_mut(&mut self, position: Position) -> &mut CompactCell {
+        let index = self.index(position);
+        &mut self.cells[index]
+    }
+
+    /// Returns city with given interned id
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    /// - `id` - interned id of city
+    ///
+    /// # Returns
+    ///
+    /// [`CompactCity`] reference or `None` if city does not exist
+    pub fn city(&self, id: EntityIndex) -> Option<&CompactCity> {
+        self.cities.iter().find(|city| city.id == id)
+    }
+
+    /// Returns mutable city with given interned id
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - mutable Self reference
+    /// - `id` - interned id of city
+    ///
+    /// # Returns
+    ///
+    /// Mutable [`CompactCity`] reference or `None` if city does not exist
+    pub fn city_mut(&mut self, id: EntityIndex) -> Option<&mut CompactCity> {
+        self.cities.iter_mut().find(|city| city.id == id)
+    }
+
+    /// Returns team of city tile at `position`
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    /// - `position` - [`Position`] of cell
+    ///
+    /// # Returns
+    ///
+    /// Team id or `None` if there is no city tile
+    pub fn city_tile_team(&self, position: Position) -> Option<TeamId> {
+        (0..TEAM_COUNT).find(|team| self.city_tile_occupancy[*team as usize].get(position))
+    }
+
+    /// Whether or not any unit stands on `position`
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    /// - `position` - [`Position`] of cell
+    ///
+    /// # Returns
+    ///
+    /// `bool` value
+    pub fn is_occupied(&self, position: Position) -> bool {
+        self.unit_occupancy.iter().any(|grid| grid.get(position))
+    }
+
+    /// Moves unit with given index in [`CompactState::units`] and updates
+    /// occupancy
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - mutable Self reference
+    /// - `index` - index of unit
+    /// - `position` - new [`Position`] of unit
+    ///
+    /// # Returns
+    ///
+    /// Nothing
+    pub fn move_unit(&mut self, index: usize, position: Position) {
+        let unit = self.units[index];
+        self.units[index].pos = position;
+        let stays = self
+            .units
+            .iter()
+            .any(|other| other.team == unit.team && other.pos == unit.pos);
+        let grid = &mut self.unit_occupancy[unit.team as usize];
+        grid.set(unit.pos, stays);
+        grid.set(position, true);
+    }
+
+    /// Rebuilds occupancy of units and city tiles from units and cells
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - mutable Self reference
+    ///
+    /// # Returns
+    ///
+    /// Nothing
+    pub fn rebuild_occupancy(&mut self) {
+        self.unit_occupancy = Default::default();
+        self.city_tile_occupancy = Default::default();
+        for unit in self.units.iter() {
+            self.unit_occupancy[unit.team as usize].set(unit.pos, true);
+        }
+        for (index, cell) in self.cells.iter().enumerate() {
+            if let Some(city) = cell.city.and_then(|id| self.city(id)) {
+                let position = self.position(index);
+                self.city_tile_occupancy[city.team as usize].set(position, true);
+            }
+        }
+    }
+
+    /// Returns id of interned entity
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    /// - `index` - interned id
+    ///
+    /// # Returns
+    ///
+    /// Entity id
+    pub fn entity_id(&self, index: EntityIndex) -> &str { &self.ids[index as usize] }
+
+    /// Interns entity id, e.g. of unit built during search
+    ///
+    /// Ids are shared between clones until new id is interned, then ids of
+    /// this state are copied once
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - mutable Self reference
+    /// - `id` - entity id
+    ///
+    /// # Returns
+    ///
+    /// Interned id
+    pub fn intern(&mut self, id: &str) -> EntityIndex {
+        match self.ids.iter().position(|known| known == id) {
+            Some(index) => index as EntityIndex,
+            None => {
+                let ids = Arc::make_mut(&mut self.ids);
+                ids.push(id.to_string());
+                (ids.len() - 1) as EntityIndex
+            },
+        }
+    }
+
+    fn index(&self, position: Position) -> usize {
+        debug_assert!(
+            (0..self.width).contains(&position.x) && (0..self.height).contains(&position.y),
+            "{} is outside of {}x{} map",
+            position,
+            self.width,
+            self.height
+        );
+        (position.y * self.width + position.x) as usize
+    }
+
+    fn position(&self, index: usize) -> Position {
+        let index = index as Coordinate;
+        Position::new(index % self.width, index / self.width)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn agent() -> Agent {
+        let mut agent = MapGenerator::new(7, 16).unwrap().generate().to_agent(0);
+        agent.turn = 12;
+        agent.players[1].research_points = 30;
+        agent.game_map[Position::new(3, 4)].road = 1.5;
+        agent.players[0].units[0].cargo.wood = 40;
+        agent
+    }
+
+    #[test]
+    fn agent_round_trips_through_compact_state() {
+        let agent = agent();
+        let state = CompactState::from_agent(&agent).unwrap();
+        let restored = state.to_agent();
+
+        assert_eq!((restored.team, restored.turn), (agent.team, agent.turn));
+        for (restored_row, row) in restored.game_map.map.iter().zip(agent.game_map.map.iter()) {
+            for (restored_cell, cell) in restored_row.iter().zip(row.iter()) {
+                assert_eq!(restored_cell.resource, cell.resource);
+                assert_eq!(restored_cell.road, cell.road);
+                assert_eq!(
+                    restored.game_map.city_tile_at(restored_cell.pos),
+                    agent.game_map.city_tile_at(cell.pos)
+                );
+            }
+        }
+        for (restored_player, player) in restored.players.iter().zip(agent.players.iter()) {
+            assert_eq!(restored_player.research_points, player.research_points);
+            assert_eq!(restored_player.city_tile_count, player.city_tile_count);
+            assert_eq!(restored_player.units, player.units);
+            for (id, city) in player.cities.iter() {
+                let restored_city = &restored_player.cities[id];
+                assert_eq!(restored_city.fuel, city.fuel);
+                assert_eq!(restored_city.light_upkeep, city.light_upkeep);
+            }
+        }
+        assert_eq!(CompactState::from_agent(&restored).unwrap(), state);
+    }
+
+    #[test]
+    fn map_larger_than_grid_is_rejected() {
+        let players = (0..TEAM_COUNT).map(Player::new).collect();
+        let agent = Simulator::new(0, GameMap::new(40, 40), players).to_agent(0);
+        assert!(matches!(
+            CompactState::from_agent(&agent),
+            Err(LuxAiError::UnsupportedMapSize(40))
+        ));
+    }
+
+    #[test]
+    #[should_panic]
+    fn position_outside_of_map_is_caught() {
+        let state = CompactState::from_agent(&agent()).unwrap();
+        state.cell(Position::new(16, 0));
+    }
+}
@@ -0,0 +1,185 @@
+use std::{collections::HashMap, fmt};
+
+use crate::*;
+
+/// Moves granted and denied by [`MoveCoordinator`]
+#[derive(Clone, Default, PartialEq, fmt::Debug)]
+pub struct MoveResolution {
+    /// Move actions which do not collide with each other, in request order
+    pub actions: Vec<Action>,
+
+    /// Ids of units whose moves were denied, so they stay in place
+    pub denied: Vec<EntityId>,
+}
+
+/// Requested move of one unit
+struct MoveRequest {
+    unit_id:   EntityId,
+    origin:    Position,
+    target:    Option<Position>,
+    direction: Direction,
+}
+
+/// Resolves desired moves of all friendly units jointly, so no two of them end
+/// up on the same cell outside of friendly city and no two of them swap cells
+///
+/// Requests are granted in the order they are made, earlier requests have
+/// priority. A move into a cell that is vacated by another granted move is
+/// granted as well, so chains of units follow each other. Cycles without a
+/// free cell, e.g. four units rotating around a square, are denied as a whole.
+/// Units of other team are expected to stay in place, their moves can not be
+/// foreseen
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// # use lux_ai_api::*;
+/// # let agent = MapGenerator::new(1, 12)?.generate().to_agent(0);
+/// # let mut environment = Environment::with_io(io::empty(), Vec::new());
+/// let mut coordinator = MoveCoordinator::from_agent(&agent);
+/// for unit in agent.player().units.iter() {
+///     coordinator.request(unit, Direction::North);
+/// }
+/// for action in coordinator.resolve().actions {
+///     environment.write_action(action);
+/// }
+/// # Ok::<(), LuxAiError>(())
+/// ```
+///
+/// # See also
+///
+/// Check <https://www.lux-ai.org/specs-2021#Units>
+pub struct MoveCoordinator<'a> {
+    game_map:  &'a GameMap,
+    team:      TeamId,
+    occupancy: HashMap<Position, usize>,
+    requests:  Vec<MoveRequest>,
+}
+
+impl<'a> MoveCoordinator<'a> {
+    /// Creates [`MoveCoordinator`] for units of given team
+    ///
+    /// # Parameters
+    ///
+    /// - `game_map` - [`GameMap`] of current turn
+    /// - `players` - all [`players`][Player], whose units occupy cells
+    /// - `team` - team id of moving units
+    ///
+    /// # Returns
+    ///
+    /// A new created [`MoveCoordinator`] without requests
+    pub fn new(game_map: &'a GameMap, players: &[Player], team: TeamId) -> Self {
+        let mut occupancy = HashMap::new();
+        for unit in players.iter().flat_map(|player| player.units.iter()) {
+            *occupancy.entry(unit.pos).or_default() += 1;
+        }
+        Self {
+            game_map,
+            team,
+            occupancy,
+            requests: vec![],
+        }
+    }
+
+    /// Creates [`MoveCoordinator`] for units of [`Agent`]'s team
+    ///
+    /// # Parameters
+    ///
+    /// - `agent` - [`Agent`] reference
+    ///
+    /// # Returns
+    ///
+    /// A new created [`MoveCoordinator`] without requests
+    pub fn from_agent(agent: &'a Agent) -> Self {
+        Self::new(&agent.game_map, &agent.players, agent.team)
+    }
+
+    /// Requests move of `unit` to given `direction`
+    ///
+    /// Requests of units on cooldown, moves off the map or into enemy city tile
+    /// are always denied, moves to [`Direction::Center`] are ignored
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - mutable Self reference
+    /// - `unit` - moving [`Unit`] of coordinator's team
+    /// - `direction` - [`Direction`] to move to
+    ///
+    /// # Returns
+    ///
+    /// Nothing
+    pub fn request(&mut self, unit: &Unit, direction: Direction) {
+        let target = match unit.can_act() {
+            true => self.game_map.translate(unit.pos, direction, 1),
+            false => Some(unit.pos),
+        };
+        self.requests.push(MoveRequest {
+            unit_id: unit.id.clone(),
+            origin: unit.pos,
+            target,
+            direction,
+        });
+    }
+
+    /// Resolves all requests into moves that do not collide
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self value
+    ///
+    /// # Returns
+    ///
+    /// [`MoveResolution`] with granted move actions and denied unit ids
+    pub fn resolve(mut self) -> MoveResolution {
+        let mut granted = vec![false; self.requests.len()];
+        loop {
+            let mut changed = false;
+            for (index, request) in self.requests.iter().enumerate() {
+                let target = match request.target {
+                    Some(target) if !granted[index] && target != request.origin => target,
+                    _ => continue,
+                };
+                let swapped = self
+                    .requests
+                    .iter()
+                    .zip(granted.iter())
+                    .any(|(other, granted)| {
+                        *granted && other.origin == target && other.target == Some(request.origin)
+                    });
+                if !swapped && self.can_enter(target) {
+                    *self.occupancy.entry(request.origin).or_default() -= 1;
+                    *self.occupancy.entry(target).or_default() += 1;
+                    granted[index] = true;
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        let mut resolution = MoveResolution::default();
+        for (request, granted) in self.requests.into_iter().zip(granted) {
+            if granted {
+                resolution.actions.push(ActionKind::Move {
+                    unit_id:   request.unit_id,
+                    direction: request.direction,
+                });
+            } else if request.direction != Direction::Center {
+                resolution.denied.push(request.unit_id);
+            }
+        }
+        resolution
+    }
+
+    fn can_enter(&self, position: Position) -> bool {
+        match self.game_map.city_tile_at(position) {
+            Some(city_tile) => city_tile.teamid == self.team,
+            None => self
+                .occupancy
+                .get(&position)
+                .is_none_or(|count| *count == 0),
+        }
+    }
+}
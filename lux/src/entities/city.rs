@@ -1,4 +1,4 @@
-use std::{cell::RefCell, fmt, rc::Rc};
+use std::fmt;
 
 use crate::*;
 
@@ -26,8 +26,8 @@ pub struct City {
 
     /// A list of [`CityTile`] objects that form this one City collectively. A
     /// City is defined as all [`CityTiles`][CityTile] that are connected
-    /// via adjacent [`CityTiles`][CityTile].
-    pub citytiles: Vec<Rc<RefCell<CityTile>>>,
+    /// via adjacent [`CityTiles`][CityTile]. Tiles are stored in [`GameMap`]
+    pub citytiles: Vec<CityTileId>,
 
     /// Light upkeep per turn of the City. Fuel in the City is subtracted by the
     /// light upkeep each turn of night.
@@ -68,20 +68,39 @@ impl City {
         }
     }
 
-    /// Add [`CityTile`] to the [`City`]
+    /// Add [`CityTile`] to the [`City`] and place it on [`GameMap`]
     ///
     /// # Parameters
     ///
     /// - `self` - mutable reference to Self
+    /// - `game_map` - mutable reference to [`GameMap`], which stores city tile
     /// - `position` - [`Position`] of [`CityTile`]
     /// - `cooldown` - cooldown of [`City`]
     ///
     /// # Returns
     ///
-    /// Nothing
-    pub fn add_city_tile(&mut self, position: Position, cooldown: Cooldown) {
+    /// [`CityTileId`] of added [`CityTile`]
+    pub fn add_city_tile(
+        &mut self, game_map: &mut GameMap, position: Position, cooldown: Cooldown,
+    ) -> CityTileId {
         let city_tile = CityTile::new(self.teamid, self.cityid.clone(), position, cooldown);
-        self.citytiles.push(Rc::new(RefCell::new(city_tile)));
+        let id = game_map.add_city_tile(city_tile);
+        self.citytiles.push(id);
+        id
+    }
+
+    /// Returns [`CityTiles`][CityTile] of the [`City`]
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - reference to Self
+    /// - `game_map` - reference to [`GameMap`], which stores city tiles
+    ///
+    /// # Returns
+    ///
+    /// Iterator over [`CityTile`] references
+    pub fn city_tiles<'a>(&'a self, game_map: &'a GameMap) -> impl Iterator<Item = &'a CityTile> {
+        self.citytiles.iter().map(move |id| &game_map[*id])
     }
 
     /// Light upkeep per turn of the [`City`]. Fuel in the [`City`] is
@@ -96,6 +115,72 @@ impl City {
     /// Fuel amount
     pub fn get_light_upkeep(&self) -> FuelAmount { self.light_upkeep }
 
+    /// Light upkeep of one [`CityTile`] with given count of adjacent friendly
+    /// city tiles, each of them reduces upkeep by `city_adjacency_bonus`
+    ///
+    /// # Parameters
+    ///
+    /// - `adjacent_tiles` - count of adjacent city tiles of the same team
+    ///
+    /// # Returns
+    ///
+    /// Fuel amount
+    ///
+    /// # See also
+    ///
+    /// Check <https://www.lux-ai.org/specs-2021#Day/Night%20Cycle>
+    pub fn tile_light_upkeep(adjacent_tiles: usize) -> FuelAmount {
+        let parameters = &GAME_CONSTANTS.parameters;
+        parameters.light_upkeep[&ObjectType::City] -
+            parameters.city_adjacency_bonus * adjacent_tiles as FuelAmount
+    }
+
+    /// Returns count of city tiles of `team` adjacent to `position`
+    ///
+    /// # Parameters
+    ///
+    /// - `game_map` - reference to [`GameMap`], which stores city tiles
+    /// - `team` - team id of city tiles to count
+    /// - `position` - [`Position`] to count neighbors of
+    ///
+    /// # Returns
+    ///
+    /// City tiles count, from `0` to `4`
+    pub fn adjacent_city_tiles(game_map: &GameMap, team: TeamId, position: Position) -> usize {
+        game_map
+            .neighbors(position)
+            .filter(|neighbor| {
+                game_map
+                    .city_tile_at(*neighbor)
+                    .is_some_and(|city_tile| city_tile.teamid == team)
+            })
+            .count()
+    }
+
+    /// Computes light upkeep of the [`City`] from its
+    /// [`CityTiles`][CityTile] and their adjacency, the same way referee does
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - reference to Self
+    /// - `game_map` - reference to [`GameMap`], which stores city tiles
+    ///
+    /// # Returns
+    ///
+    /// Fuel amount
+    ///
+    /// # See also
+    ///
+    /// Check <https://www.lux-ai.org/specs-2021#Day/Night%20Cycle>
+    pub fn compute_light_upkeep(&self, game_map: &GameMap) -> FuelAmount {
+        self.city_tiles(game_map)
+            .map(|city_tile| {
+                let adjacent = Self::adjacent_city_tiles(game_map, self.teamid, city_tile.pos);
+                Self::tile_light_upkeep(adjacent)
+            })
+            .sum()
+    }
+
     /// How many resources required to build city
     ///
     /// # Arguments
@@ -106,4 +191,33 @@ impl City {
     ///
     /// `ResourceAmount` value
     pub fn city_build_cost() -> ResourceAmount { GAME_CONSTANTS.parameters.city_build_cost }
+
+    /// Projects whether the [`City`] survives to the end of next night with
+    /// its current fuel
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - reference to Self
+    /// - `turn` - turn index, counted the same way as [`Agent::turn`]
+    ///
+    /// # Returns
+    ///
+    /// [`NightForecast`] of the [`City`]
+    ///
+    /// # See also
+    ///
+    /// Check <https://www.lux-ai.org/specs-2021#Day/Night%20Cycle>
+    pub fn night_forecast(&self, turn: TurnAmount) -> NightForecast {
+        let night_turns = GameClock::new(turn).night_turns_left();
+        let turns_of_fuel = if self.light_upkeep > 0.0 {
+            (self.fuel / self.light_upkeep).floor() as TurnAmount
+        } else {
+            night_turns
+        };
+        NightForecast {
+            night_turns,
+            turns_of_fuel,
+            fuel_needed: (self.light_upkeep * night_turns as FuelAmount - self.fuel).max(0.0),
+        }
+    }
 }
@@ -1,13 +1,34 @@
 use std::fmt;
 
+use serde::{Deserialize, Serialize};
+
 use crate::*;
 
+/// Handle of [`CityTile`] stored in [`GameMap`]
+///
+/// Handle is valid only for [`GameMap`] which created it and its clones, until
+/// city tile is removed or map is reset
+///
+/// # Examples
+///
+/// ```
+/// # use lux_ai_api::*;
+/// # let agent = MapGenerator::new(1, 12)?.generate().to_agent(0);
+/// # let city = agent.player().cities.values().next().unwrap();
+/// for id in city.citytiles.iter() {
+///     let city_tile = &agent.game_map[*id];
+/// }
+/// # Ok::<(), LuxAiError>(())
+/// ```
+#[derive(Eq, PartialEq, Clone, Copy, fmt::Debug, Hash, Serialize, Deserialize)]
+pub struct CityTileId(pub(crate) usize);
+
 /// Represents City tile of given team at position
 ///
 /// # See also
 ///
 /// Check <https://www.lux-ai.org/specs-2021#CityTiles>
-#[derive(Clone, PartialEq, fmt::Debug)]
+#[derive(Clone, PartialEq, fmt::Debug, Serialize, Deserialize)]
 pub struct CityTile {
     /// City id used as command arguments
     pub cityid: EntityId,
@@ -85,9 +106,7 @@ impl CityTile {
     /// # See also:
     ///
     /// Check <https://www.lux-ai.org/specs-2021#CityTiles>
-    pub fn research(&self) -> Action {
-        format!("{} {}", Commands::RESEARCH, self.pos.to_argument())
-    }
+    pub fn research(&self) -> Action { ActionKind::Research { position: self.pos } }
 
     /// Returns build worker action. When applied and requirements are met, a
     /// worker will be built at the [`City`].
@@ -103,9 +122,7 @@ impl CityTile {
     /// # See also:
     ///
     /// Check <https://www.lux-ai.org/specs-2021#CityTiles>
-    pub fn build_worker(&self) -> Action {
-        format!("{} {}", Commands::BUILD_WORKER, self.pos.to_argument())
-    }
+    pub fn build_worker(&self) -> Action { ActionKind::BuildWorker { position: self.pos } }
 
     /// Returns the build cart action. When applied and requirements are met, a
     /// cart will be built at the [`City`].
@@ -121,7 +138,5 @@ impl CityTile {
     /// # See also:
     ///
     /// Check <https://www.lux-ai.org/specs-2021#CityTiles>
-    pub fn build_cart(&self) -> Action {
-        format!("{} {}", Commands::BUILD_CART, self.pos.to_argument())
-    }
+    pub fn build_cart(&self) -> Action { ActionKind::BuildCart { position: self.pos } }
 }
@@ -1,7 +1,5 @@
-use std::{cell::RefCell,
-          convert::{From, Into, TryFrom},
-          ops::{Index, IndexMut},
-          rc::Rc};
+use std::{convert::{From, Into, TryFrom},
+          ops::{Index, IndexMut}};
 
 use crate::*;
 
@@ -75,7 +73,7 @@ pub struct Cell {
     ///
     /// Check <https://www.lux-ai.org/specs-2021#The%20Map>
     /// Check <https://www.lux-ai.org/specs-2021#CityTiles>
-    pub citytile: Option<Rc<RefCell<CityTile>>>,
+    pub citytile: Option<CityTileId>,
 
     /// Road development progress of this tile
     ///
@@ -167,6 +165,10 @@ pub struct GameMap {
     ///
     /// Check <https://www.lux-ai.org/specs-2021#The%20Map>
     pub map: Vec<Vec<Cell>>,
+
+    /// Arena of [`CityTiles`][CityTile] indexed by [`CityTileId`], removed
+    /// tiles leave empty slots so handles of other tiles stay valid
+    citytiles: Vec<Option<CityTile>>,
 }
 
 /// Access [cells][`Cell`] by [`Position`]
@@ -190,6 +192,10 @@ impl Index<Position> for GameMap {
     /// # Returns
     ///
     /// Reference to [`Cell`]
+    ///
+    /// # Panics
+    ///
+    /// If `position` is outside of map, use [`GameMap::get`] otherwise
     fn index(&self, position: Position) -> &Self::Output {
         &self.map[position.y as usize][position.x as usize]
     }
@@ -212,6 +218,51 @@ impl IndexMut<Position> for GameMap {
     }
 }
 
+/// Access [city tiles][`CityTile`] by [`CityTileId`]
+impl Index<CityTileId> for GameMap {
+    type Output = CityTile;
+
+    /// Returns the [`CityTile`] with the given `id`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use lux_ai_api::*;
+    /// # let agent = MapGenerator::new(1, 12)?.generate().to_agent(0);
+    /// # let game_map = &agent.game_map;
+    /// # let city = agent.player().cities.values().next().unwrap();
+    /// let city_tile = &game_map[city.citytiles[0]];
+    /// # Ok::<(), LuxAiError>(())
+    /// ```
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    /// - `id` - [`CityTileId`] of existing [`CityTile`]
+    ///
+    /// # Returns
+    ///
+    /// Reference to [`CityTile`]
+    fn index(&self, id: CityTileId) -> &Self::Output { self.citytiles[id.0].as_ref().unwrap() }
+}
+
+/// Access [city tiles][`CityTile`] by [`CityTileId`]
+impl IndexMut<CityTileId> for GameMap {
+    /// Returns the [`CityTile`] with the given `id`
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    /// - `id` - [`CityTileId`] of existing [`CityTile`]
+    ///
+    /// # Returns
+    ///
+    /// Reference to [`CityTile`]
+    fn index_mut(&mut self, id: CityTileId) -> &mut Self::Output {
+        self.citytiles[id.0].as_mut().unwrap()
+    }
+}
+
 impl GameMap {
     /// Creates empty `GameMap` with given `dimensions`
     ///
@@ -229,7 +280,12 @@ impl GameMap {
     /// Check <https://www.lux-ai.org/specs-2021#The%20Map>
     pub fn new(width: Coordinate, height: Coordinate) -> Self {
         let map = Self::empty_map(width as usize, height as usize);
-        Self { height, width, map }
+        Self {
+            height,
+            width,
+            map,
+            citytiles: vec![],
+        }
     }
 
     /// Returns dimensions of [`GameMap`] 2D grid
@@ -290,6 +346,168 @@ impl GameMap {
     /// Nothing
     pub fn reset_state(&mut self) {
         self.map = Self::empty_map(self.width as usize, self.height as usize);
+        self.citytiles.clear();
+    }
+
+    /// Stores [`CityTile`] and places it on [`Cell`] at its position. City
+    /// tile already placed on the cell is replaced and its [`CityTileId`] is
+    /// reused, so no arena slot is left unreachable
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - mutable Self reference
+    /// - `city_tile` - [`CityTile`] to store
+    ///
+    /// # Returns
+    ///
+    /// [`CityTileId`] of stored [`CityTile`]
+    pub fn add_city_tile(&mut self, city_tile: CityTile) -> CityTileId {
+        if let Some(id) = self[city_tile.pos].citytile {
+            self.citytiles[id.0] = Some(city_tile);
+            return id;
+        }
+        let id = CityTileId(self.citytiles.len());
+        self[city_tile.pos].citytile = Some(id);
+        self.citytiles.push(Some(city_tile));
+        id
+    }
+
+    /// Removes [`CityTile`] from arena and from [`Cell`] at its position
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - mutable Self reference
+    /// - `id` - [`CityTileId`] of [`CityTile`] to remove
+    ///
+    /// # Returns
+    ///
+    /// Removed [`CityTile`] or `None` if there is no city tile with `id`
+    pub fn remove_city_tile(&mut self, id: CityTileId) -> Option<CityTile> {
+        let city_tile = self.citytiles.get_mut(id.0)?.take()?;
+        self[city_tile.pos].citytile = None;
+        Some(city_tile)
+    }
+
+    /// Returns the [`CityTile`] with the given `id`
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    /// - `id` - [`CityTileId`] of [`CityTile`]
+    ///
+    /// # Returns
+    ///
+    /// Reference to [`CityTile`] or `None` if there is no city tile with `id`
+    pub fn city_tile(&self, id: CityTileId) -> Option<&CityTile> {
+        self.citytiles.get(id.0)?.as_ref()
+    }
+
+    /// Returns the [`CityTile`] located at the given `position`
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    /// - `position` - [`Position`] of [`Cell`], inside of map
+    ///
+    /// # Returns
+    ///
+    /// Reference to [`CityTile`] or `None` if there is no city tile on cell
+    pub fn city_tile_at(&self, position: Position) -> Option<&CityTile> {
+        self[position].citytile.map(|id| &self[id])
+    }
+
+    /// Checks whether `position` is inside of [`GameMap`]
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    /// - `position` - [`Position`] to check
+    ///
+    /// # Returns
+    ///
+    /// `bool` value
+    ///
+    /// # See also
+    ///
+    /// Check <https://www.lux-ai.org/specs-2021#The%20Map>
+    pub fn contains(&self, position: Position) -> bool {
+        position.x >= 0 && position.y >= 0 && position.x < self.width && position.y < self.height
+    }
+
+    /// Returns the [`Cell`] at the given `position` if it is inside of map
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    /// - `position` - [`Position`] to get [`Cell`] from
+    ///
+    /// # Returns
+    ///
+    /// Reference to [`Cell`] or `None` if `position` is outside of map
+    pub fn get(&self, position: Position) -> Option<&Cell> {
+        self.contains(position).then(|| &self[position])
+    }
+
+    /// Returns the mutable [`Cell`] at the given `position` if it is inside of
+    /// map
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - mutable Self reference
+    /// - `position` - [`Position`] to get [`Cell`] from
+    ///
+    /// # Returns
+    ///
+    /// Mutable reference to [`Cell`] or `None` if `position` is outside of map
+    pub fn get_mut(&mut self, position: Position) -> Option<&mut Cell> {
+        match self.contains(position) {
+            true => Some(&mut self[position]),
+            false => None,
+        }
+    }
+
+    /// Returns the [`Position`] equal to going in a `direction` `units` number
+    /// of times from `position`, if it stays inside of map
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    /// - `position` - [`Position`] to translate
+    /// - `direction` - [`Direction`] to translate to
+    /// - `units` - amount of tiles to translate to
+    ///
+    /// # Returns
+    ///
+    /// Translated [`Position`] or `None` if it is outside of map
+    ///
+    /// # See also
+    ///
+    /// Check <https://www.lux-ai.org/specs-2021#The%20Map>
+    pub fn translate(
+        &self, position: Position, direction: Direction, units: Coordinate,
+    ) -> Option<Position> {
+        let translated = position.offset(direction, units);
+        self.contains(translated).then_some(translated)
+    }
+
+    /// Returns positions adjacent to `position` which are inside of map
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    /// - `position` - [`Position`] to get neighbors of
+    ///
+    /// # Returns
+    ///
+    /// Iterator over neighbor positions in [`Direction::DIRECTIONS`] order
+    ///
+    /// # See also
+    ///
+    /// Check <https://www.lux-ai.org/specs-2021#The%20Map>
+    pub fn neighbors(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        Direction::DIRECTIONS
+            .into_iter()
+            .filter_map(move |direction| self.translate(position, direction, 1))
     }
 
     /// Returns the [`Cell`] at the given `position`
@@ -302,9 +520,12 @@ impl GameMap {
     /// # Returns
     ///
     /// Reference to [`Cell`]
-    pub fn get_cell_by_pos(&self, position: Position) -> &Cell {
-        self.get_cell(position.x, position.y)
-    }
+    ///
+    /// # Panics
+    ///
+    /// If `position` is outside of map, use [`GameMap::get`] otherwise
+    #[deprecated(note = "use `GameMap::get`, which checks map bounds")]
+    pub fn get_cell_by_pos(&self, position: Position) -> &Cell { &self[position] }
 
     /// Returns the [`Cell`] at the given `position`
     ///
@@ -317,6 +538,11 @@ impl GameMap {
     /// # Returns
     ///
     /// Reference to [`Cell`]
+    ///
+    /// # Panics
+    ///
+    /// If coordinates are outside of map, use [`GameMap::get`] otherwise
+    #[deprecated(note = "use `GameMap::get`, which checks map bounds")]
     pub fn get_cell(&self, x: Coordinate, y: Coordinate) -> &Cell {
         &self.map[y as usize][x as usize]
     }
@@ -333,3 +559,21 @@ impl GameMap {
         map
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn city_tile_on_occupied_cell_reuses_arena_slot() {
+        let mut game_map = GameMap::new(4, 4);
+        let position = Position::new(1, 2);
+        let first = game_map.add_city_tile(CityTile::new(0, "c_1".to_string(), position, 0.0));
+        let second = game_map.add_city_tile(CityTile::new(1, "c_2".to_string(), position, 1.0));
+
+        assert_eq!(first, second);
+        assert_eq!(game_map.citytiles.len(), 1);
+        assert_eq!(game_map[position].citytile, Some(first));
+        assert_eq!(game_map[first].cityid, "c_2");
+    }
+}
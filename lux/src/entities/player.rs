@@ -109,4 +109,155 @@ impl Player {
     ///
     /// Check <https://www.lux-ai.org/specs-2021#Resources>
     pub fn researched_uranium(&self) -> bool { self.is_researched(ResourceType::Uranium) }
+
+    /// Returns ids of own cities with city tiles adjacent to `position`
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    /// - `game_map` - reference to [`GameMap`], which stores city tiles
+    /// - `position` - [`Position`] to look around
+    ///
+    /// # Returns
+    ///
+    /// Distinct city ids in [`Direction::DIRECTIONS`] order of their tiles
+    pub fn adjacent_city_ids(&self, game_map: &GameMap, position: Position) -> Vec<EntityId> {
+        let mut city_ids: Vec<EntityId> = vec![];
+        for neighbor in game_map.neighbors(position) {
+            if let Some(city_tile) = game_map.city_tile_at(neighbor) {
+                if city_tile.teamid == self.team && !city_ids.contains(&city_tile.cityid) {
+                    city_ids.push(city_tile.cityid.clone());
+                }
+            }
+        }
+        city_ids
+    }
+
+    /// Predicts city resulting from building [`CityTile`] at `position`: city
+    /// new tile joins, cities merged into it, their fuel and light upkeep
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    /// - `game_map` - reference to [`GameMap`], which stores city tiles
+    /// - `position` - [`Position`] of empty cell to build on
+    ///
+    /// # Returns
+    ///
+    /// [`CityBuildPrediction`] value
+    ///
+    /// # See also
+    ///
+    /// Check <https://www.lux-ai.org/specs-2021#CityTiles>
+    pub fn predict_city_build(
+        &self, game_map: &GameMap, position: Position,
+    ) -> CityBuildPrediction {
+        let city_ids = self.adjacent_city_ids(game_map, position);
+        let cities: Vec<&City> = city_ids
+            .iter()
+            .filter_map(|city_id| self.cities.get(city_id))
+            .collect();
+
+        let adjacent = City::adjacent_city_tiles(game_map, self.team, position);
+        let mut light_upkeep = City::tile_light_upkeep(adjacent);
+        let mut city_tiles = 1;
+        for city_tile in cities.iter().flat_map(|city| city.city_tiles(game_map)) {
+            let adjacent = City::adjacent_city_tiles(game_map, self.team, city_tile.pos) +
+                (city_tile.pos.distance_to(&position) == 1.0) as usize;
+            light_upkeep += City::tile_light_upkeep(adjacent);
+            city_tiles += 1;
+        }
+
+        let mut city_ids = city_ids.into_iter();
+        CityBuildPrediction {
+            team: self.team,
+            city_id: city_ids.next(),
+            merged_city_ids: city_ids.collect(),
+            city_tiles,
+            fuel: cities.iter().map(|city| city.fuel).sum(),
+            light_upkeep,
+        }
+    }
+
+    /// Returns count of [`CityTiles`][CityTile] off cooldown, which can
+    /// research or build unit this turn
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    /// - `game_map` - reference to [`GameMap`], which stores city tiles
+    ///
+    /// # Returns
+    ///
+    /// City tiles count
+    pub fn ready_city_tiles(&self, game_map: &GameMap) -> u32 {
+        self.cities
+            .values()
+            .flat_map(|city| city.city_tiles(game_map))
+            .filter(|city_tile| city_tile.can_act())
+            .count() as u32
+    }
+
+    /// Projects when `resource_type` is unlocked if all city tiles off cooldown
+    /// keep researching
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    /// - `game_map` - reference to [`GameMap`], which stores city tiles
+    /// - `turn` - turn index, counted the same way as [`Agent::turn`]
+    /// - `resource_type` - resource type to unlock
+    ///
+    /// # Returns
+    ///
+    /// [`ResearchForecast`] of `resource_type`
+    ///
+    /// # See also
+    ///
+    /// Check <https://www.lux-ai.org/specs-2021#Resources>
+    pub fn research_forecast(
+        &self, game_map: &GameMap, turn: TurnAmount, resource_type: ResourceType,
+    ) -> ResearchForecast {
+        let points_needed =
+            (resource_type.required_research_points() - self.research_points).max(0);
+        let researching_tiles = self.ready_city_tiles(game_map);
+        let turns_until_unlocked =
+            ResearchForecast::turns_to_research(points_needed, researching_tiles);
+        ResearchForecast {
+            resource_type,
+            points_needed,
+            researching_tiles,
+            turns_until_unlocked,
+            unlock_turn: turns_until_unlocked.map(|turns| turn + turns),
+        }
+    }
+
+    /// Returns how many city tiles off cooldown should research instead of
+    /// building units, so `resource_type` is unlocked by `target_turn`
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    /// - `game_map` - reference to [`GameMap`], which stores city tiles
+    /// - `turn` - turn index, counted the same way as [`Agent::turn`]
+    /// - `resource_type` - resource type to unlock
+    /// - `target_turn` - turn on which resource should be unlocked
+    ///
+    /// # Returns
+    ///
+    /// City tiles count, `0` if resource is already unlocked, or `None` if
+    /// target turn can not be reached with city tiles off cooldown
+    ///
+    /// # See also
+    ///
+    /// Check <https://www.lux-ai.org/specs-2021#Resources>
+    pub fn research_tiles_needed(
+        &self, game_map: &GameMap, turn: TurnAmount, resource_type: ResourceType,
+        target_turn: TurnAmount,
+    ) -> Option<u32> {
+        let points_needed =
+            (resource_type.required_research_points() - self.research_points).max(0);
+        ResearchForecast::tiles_to_research(points_needed, target_turn - turn)
+            .filter(|tiles| *tiles <= self.ready_city_tiles(game_map))
+    }
 }
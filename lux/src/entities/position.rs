@@ -1,6 +1,8 @@
 use std::{convert::{TryFrom, TryInto},
           fmt};
 
+use serde::{Deserialize, Serialize};
+
 use crate::*;
 
 /// Represents coordinate (x or y) on 2D grid
@@ -11,7 +13,7 @@ pub type Coordinate = i32;
 /// # See also
 ///
 /// Check <https://www.lux-ai.org/specs-2021#The%20Map>
-#[derive(Eq, PartialEq, Clone, Copy, fmt::Debug)]
+#[derive(Eq, PartialEq, Clone, Copy, fmt::Debug, Hash, Serialize, Deserialize)]
 pub struct Position {
     /// X coordinate
     pub x: Coordinate,
@@ -60,7 +62,11 @@ impl Position {
     }
 
     /// Returns the [`Position`] equal to going in a `direction` `units` number
-    /// of times from this [`Position`]
+    /// of times from this [`Position`]. Result is not checked against map
+    /// bounds, use [`GameMap::translate`] to get only positions inside of map
+    ///
+    /// Deprecated, because unchecked result panics when used to index
+    /// [`GameMap`]
     ///
     /// # Parameters
     ///
@@ -75,7 +81,24 @@ impl Position {
     /// # See also
     ///
     /// Check <https://www.lux-ai.org/specs-2021#The%20Map>
+    #[deprecated(note = "use `GameMap::translate`, which checks map bounds")]
     pub fn translate(&self, direction: Direction, units: Coordinate) -> Self {
+        self.offset(direction, units)
+    }
+
+    /// Returns the [`Position`] equal to going in a `direction` `units` number
+    /// of times from this [`Position`], without checking map bounds
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference ([`Position `] to translate)
+    /// - `direction` - [`Direction `] to translate to
+    /// - `units` - amount of tiles to translate to
+    ///
+    /// # Returns
+    ///
+    /// Translated [`Position`]
+    pub(crate) fn offset(&self, direction: Direction, units: Coordinate) -> Self {
         match direction {
             Direction::North => Self::new(self.x, self.y - units),
             Direction::East => Self::new(self.x + units, self.y),
@@ -108,7 +131,7 @@ impl Position {
         let mut closest_direction = Direction::Center;
         let mut closest_distance = target.distance_to(self);
         for direction in Direction::DIRECTIONS {
-            let ref new_position = self.translate(direction, 1);
+            let ref new_position = self.offset(direction, 1);
             let distance = target.distance_to(new_position);
             if distance < closest_distance {
                 closest_direction = direction;
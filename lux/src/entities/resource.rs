@@ -82,7 +82,7 @@ impl ResourceType {
 /// # See also
 ///
 /// Check https://www.lux-ai.org/specs-2021#Resources<>
-#[derive(PartialEq, Clone, fmt::Debug)]
+#[derive(PartialEq, Clone, fmt::Debug, Serialize, Deserialize)]
 pub struct Resource {
     /// Type of resource
     ///
@@ -12,7 +12,7 @@ use crate::*;
 /// # See also
 ///
 /// Check <https://www.lux-ai.org/specs-2021#Resources>
-#[derive(Clone, Copy, Default, fmt::Debug)]
+#[derive(Clone, Copy, Default, PartialEq, fmt::Debug, Serialize, Deserialize)]
 pub struct Cargo {
     /// Amount of wood held by Unit
     pub wood:    ResourceAmount,
@@ -140,10 +140,26 @@ impl UnitType {
     pub fn cargo_space_available(&self) -> ResourceAmount {
         GAME_CONSTANTS.parameters.resource_capacity[self]
     }
+
+    /// Converts into command argument
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// Argument string - `0` for worker, `1` for cart
+    pub fn to_argument(&self) -> String {
+        match self {
+            Self::Worker => "0".to_string(),
+            Self::Cart => "1".to_string(),
+        }
+    }
 }
 
 /// Represents Unit on [`GameMap`]
-#[derive(Clone, fmt::Debug)]
+#[derive(Clone, PartialEq, fmt::Debug, Serialize, Deserialize)]
 pub struct Unit {
     /// [`Position`] of unit on 2D grid
     pub pos: Position,
@@ -267,6 +283,96 @@ impl Unit {
             self.cargo_space_used() >= City::city_build_cost()
     }
 
+    /// Returns fuel amount of all resources in Unit's cargo
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// [`FuelAmount`] value
+    pub fn cargo_fuel(&self) -> FuelAmount {
+        let fuel_rates = &GAME_CONSTANTS.parameters.resource_to_fuel_rate;
+        ResourceType::VALUES
+            .into_iter()
+            .map(|resource_type| {
+                self.cargo[resource_type] as FuelAmount * fuel_rates[&resource_type]
+            })
+            .sum()
+    }
+
+    /// Burns cargo to survive one night turn outside of [`City`]: wood first,
+    /// then coal, then uranium
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - mutable reference to Self
+    ///
+    /// # Returns
+    ///
+    /// `true` if cargo was enough to survive, otherwise unit dies
+    ///
+    /// # See also
+    ///
+    /// Check <https://www.lux-ai.org/specs-2021#Day/Night%20Cycle>
+    pub fn spend_fuel_to_survive(&mut self) -> bool {
+        let fuel_rates = &GAME_CONSTANTS.parameters.resource_to_fuel_rate;
+        let mut fuel_needed =
+            GAME_CONSTANTS.parameters.light_upkeep[&ObjectType::Unit(self.unit_type)];
+        for resource_type in ResourceType::VALUES {
+            let rate = fuel_rates[&resource_type];
+            let needed = (fuel_needed / rate).ceil() as ResourceAmount;
+            let used = self.cargo[resource_type].min(needed);
+            self.cargo[resource_type] -= used;
+            fuel_needed -= used as FuelAmount * rate;
+            if fuel_needed <= 0.0 {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Projects whether the Unit survives to the end of next night outside of
+    /// [`City`] with its current cargo
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - reference to Self
+    /// - `turn` - turn index, counted the same way as [`Agent::turn`]
+    ///
+    /// # Returns
+    ///
+    /// [`NightForecast`] of the Unit
+    ///
+    /// # See also
+    ///
+    /// Check <https://www.lux-ai.org/specs-2021#Day/Night%20Cycle>
+    pub fn night_forecast(&self, turn: TurnAmount) -> NightForecast {
+        let night_turns = GameClock::new(turn).night_turns_left();
+        let light_upkeep =
+            GAME_CONSTANTS.parameters.light_upkeep[&ObjectType::Unit(self.unit_type)];
+
+        let mut unit = self.clone();
+        let mut turns_of_fuel = 0;
+        let mut fuel_left = unit.cargo_fuel();
+        while unit.spend_fuel_to_survive() {
+            turns_of_fuel += 1;
+            fuel_left = unit.cargo_fuel();
+        }
+
+        let fuel_needed = if turns_of_fuel >= night_turns {
+            0.0
+        } else {
+            (night_turns - turns_of_fuel) as FuelAmount * light_upkeep - fuel_left
+        };
+        NightForecast {
+            night_turns,
+            turns_of_fuel,
+            fuel_needed,
+        }
+    }
+
     /// Check if Unit can pillage road, i.e. cooldown is 0 and unit is worker
     /// and road development progress > 0
     ///
@@ -298,7 +404,10 @@ impl Unit {
     ///
     /// Action to perform
     pub fn move_(&self, direction: Direction) -> Action {
-        format!("{} {} {}", Commands::MOVE, self.id, direction.to_argument())
+        ActionKind::Move {
+            unit_id: self.id.clone(),
+            direction,
+        }
     }
 
     /// Returns action to transfer resource to other (`to_unit`) [`Unit`]
@@ -316,14 +425,12 @@ impl Unit {
     pub fn transfer(
         &self, to_unit: &Unit, resource_type: ResourceType, resource_amount: ResourceAmount,
     ) -> Action {
-        format!(
-            "{} {} {} {} {}",
-            Commands::TRANSFER,
-            self.id,
-            to_unit.id,
-            resource_type.to_argument(),
-            resource_amount
-        )
+        ActionKind::Transfer {
+            unit_id: self.id.clone(),
+            destination_id: to_unit.id.clone(),
+            resource_type,
+            amount: resource_amount,
+        }
     }
 
     /// Returns action to build [`City`]
@@ -335,7 +442,11 @@ impl Unit {
     /// # Returns
     ///
     /// Action to perform
-    pub fn build_city(&self) -> Action { format!("{} {}", Commands::BUILD_CITY, self.id) }
+    pub fn build_city(&self) -> Action {
+        ActionKind::BuildCity {
+            unit_id: self.id.clone(),
+        }
+    }
 
     /// Returns action to pillage road
     ///
@@ -346,5 +457,9 @@ impl Unit {
     /// # Returns
     ///
     /// Action to perform
-    pub fn pillage(&self) -> Action { format!("{} {}", Commands::PILLAGE, self.id) }
+    pub fn pillage(&self) -> Action {
+        ActionKind::Pillage {
+            unit_id: self.id.clone(),
+        }
+    }
 }
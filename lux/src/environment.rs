@@ -1,16 +1,34 @@
-use std::io::{prelude::*, BufRead, BufReader, BufWriter};
+use std::{collections::HashMap,
+          io::{prelude::*, BufRead, BufReader, BufWriter}};
 
 use super::*;
 
 /// Represents Action performed by Agent
-pub type Action = String;
-
+pub type Action = ActionKind;
 
 /// Environment wrapper to interact with Lux AI API I/O
-pub struct Environment {
-    reader:  BufReader<io::Stdin>,
-    writer:  BufWriter<io::Stdout>,
-    actions: Vec<Action>,
+///
+/// Generic over transport: reads observations from any `BufRead` and writes
+/// actions into any `Write`, stdin and stdout by default
+///
+/// # Examples
+///
+/// ```no_run
+/// # use std::io;
+/// # use lux_ai_api::*;
+/// let observations = std::fs::read("observations.txt")?;
+/// let mut environment = Environment::with_io(io::Cursor::new(observations), Vec::new());
+/// let mut agent = Agent::new(&mut environment)?;
+/// agent.update_turn(&mut environment)?;
+/// # Ok::<(), LuxAiError>(())
+/// ```
+pub struct Environment<R: BufRead = BufReader<io::Stdin>, W: Write = BufWriter<io::Stdout>> {
+    reader:                 R,
+    writer:                 W,
+    actions:                Vec<Action>,
+    line:                   usize,
+    unknown_command_policy: UnknownCommandPolicy,
+    command_handlers:       HashMap<CommandArgument, CommandHandler>,
 }
 
 impl Environment {
@@ -24,22 +42,151 @@ impl Environment {
     ///
     /// A new created `Environment`
     pub fn new() -> Self {
+        Self::with_io(BufReader::new(io::stdin()), BufWriter::new(io::stdout()))
+    }
+}
+
+impl<R: BufRead, W: Write> Environment<R, W> {
+    /// Initializes Environment with given reader and writer
+    ///
+    /// # Parameters
+    ///
+    /// - `reader` - `BufRead` to read observations from
+    /// - `writer` - `Write` to write actions into
+    ///
+    /// # Returns
+    ///
+    /// A new created `Environment`
+    pub fn with_io(reader: R, writer: W) -> Self {
         Self {
-            reader:  BufReader::new(io::stdin()),
-            writer:  BufWriter::new(io::stdout()),
+            reader,
+            writer,
             actions: vec![],
+            line: 0,
+            unknown_command_policy: UnknownCommandPolicy::default(),
+            command_handlers: HashMap::new(),
+        }
+    }
+
+    /// Returns policy applied to unknown input commands
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// [`UnknownCommandPolicy`] value
+    pub fn unknown_command_policy(&self) -> UnknownCommandPolicy { self.unknown_command_policy }
+
+    /// Sets policy applied to unknown input commands without registered
+    /// handler
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - mutable Self reference
+    /// - `policy` - [`UnknownCommandPolicy`] to apply
+    ///
+    /// # Returns
+    ///
+    /// Nothing
+    pub fn set_unknown_command_policy(&mut self, policy: UnknownCommandPolicy) {
+        self.unknown_command_policy = policy;
+    }
+
+    /// Registers handler of input command not defined by protocol, replacing
+    /// previous handler of the same command type. Commands defined by protocol
+    /// are never passed to handlers
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use lux_ai_api::*;
+    /// # let mut turn_handler = |_: &Agent, _: &mut Environment| -> LuxAiResult { Ok(()) };
+    /// let mut environment = Environment::new();
+    /// environment.register_command_handler("weather", |agent, command| {
+    ///     eprintln!("Turn {}: weather is {}", agent.turn, command.argument::<String>(1)?);
+    ///     Ok(())
+    /// });
+    /// environment.run_with_agent(&mut turn_handler)?;
+    /// # Ok::<(), LuxAiError>(())
+    /// ```
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - mutable Self reference
+    /// - `command_type` - first token of command lines to handle
+    /// - `handler` - function called with [`Agent`] being updated and
+    ///   [`Command`] read
+    ///
+    /// # Returns
+    ///
+    /// Nothing
+    pub fn register_command_handler<F>(&mut self, command_type: &str, handler: F)
+    where
+        F: FnMut(&mut Agent, &Command) -> LuxAiResult + 'static,
+    {
+        self.command_handlers
+            .insert(command_type.to_string(), Box::new(handler));
+    }
+
+    /// Passes unknown input command to its registered handler or applies
+    /// [`UnknownCommandPolicy`]
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - mutable Self reference
+    /// - `agent` - [`Agent`] being updated
+    /// - `command` - unknown [`Command`] read last
+    ///
+    /// # Returns
+    ///
+    /// Nothing or error of handler or [`UnknownCommandPolicy::Fail`] policy
+    pub fn handle_unknown_command(&mut self, agent: &mut Agent, command: Command) -> LuxAiResult {
+        let handler = command
+            .arguments
+            .first()
+            .and_then(|command_type| self.command_handlers.get_mut(command_type));
+        if let Some(handler) = handler {
+            return handler(agent, &command);
+        }
+
+        let error = || LuxAiError::UnknownCommand(self.line, agent.turn, command.arguments.clone());
+        match self.unknown_command_policy {
+            UnknownCommandPolicy::Ignore => {},
+            UnknownCommandPolicy::Warn => eprintln!("{}", error()),
+            UnknownCommandPolicy::Collect => agent.unknown_commands.push(command),
+            UnknownCommandPolicy::Fail => Err(error())?,
         }
+        Ok(())
     }
 
+    /// Returns reference to underlying writer, e.g. to inspect written actions
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// Writer reference
+    pub fn writer(&self) -> &W { &self.writer }
+
+    /// Destructs Environment into underlying reader and writer
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self value
+    ///
+    /// # Returns
+    ///
+    /// Pair of reader and writer
+    pub fn into_io(self) -> (R, W) { (self.reader, self.writer) }
+
     /// Runs whole match with initialized `Agent`
     ///
-    /// - Initializes `Agent`
-    /// - On each turn:
-    ///     - Updates `Agent`
-    ///     - Runs `turn_handler` with `Agent` and `Environment`
-    ///     - Flush all actions
-    ///     - Flushes I/O
-    ///     - Ends turn
+    /// Same as [`Environment::run_with_budget`] with default [`TimeBudget`],
+    /// which is not passed to `turn_handler`
     ///
     /// # Examples
     ///
@@ -65,6 +212,54 @@ impl Environment {
     /// Nothing or error
     pub fn run_with_agent<F: FnMut(&Agent, &mut Self) -> LuxAiResult>(
         &mut self, turn_handler: &mut F,
+    ) -> LuxAiResult {
+        self.run_with_budget(TimeBudget::default(), &mut |agent, environment, _| {
+            turn_handler(agent, environment)
+        })
+    }
+
+    /// Runs whole match with initialized `Agent` and per-turn time budget
+    ///
+    /// - Initializes `Agent`
+    /// - On each turn:
+    ///     - Updates `Agent`
+    ///     - Starts turn timer of `budget`
+    ///     - Runs `turn_handler` with `Agent`, `Environment` and `TimeBudget`
+    ///     - Flush all actions, even if `turn_handler` failed
+    ///     - Flushes I/O
+    ///     - Ends turn and charges overrun to overage
+    ///     - Returns error of `turn_handler` if any
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use lux_ai_api::*;
+    /// fn turn_handler(agent: &Agent, environment: &mut Environment, budget: &TimeBudget) -> LuxAiResult {
+    ///   while !budget.is_expired() {
+    ///     // Do something
+    ///   }
+    ///   Ok(())
+    /// };
+    ///
+    /// // ...
+    ///
+    /// let mut environment = Environment::new();
+    /// environment.run_with_budget(TimeBudget::default(), &mut turn_handler)?;
+    /// # Ok::<(), LuxAiError>(())
+    /// ```
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - mutable Self reference
+    /// - `budget` - `TimeBudget` with turn and overage limits
+    /// - `turn_handler` - Function reference with Agent, Environment and
+    ///   TimeBudget arguments
+    ///
+    /// # Returns
+    ///
+    /// Nothing or error
+    pub fn run_with_budget<F: FnMut(&Agent, &mut Self, &TimeBudget) -> LuxAiResult>(
+        &mut self, mut budget: TimeBudget, turn_handler: &mut F,
     ) -> LuxAiResult {
         let mut agent = Agent::new(self)?;
 
@@ -73,12 +268,15 @@ impl Environment {
                 Err(LuxAiError::EmptyInput) => break,
                 result => result?,
             };
-            turn_handler(&agent, self)?;
+            budget.start_turn();
+            let result = turn_handler(&agent, self, &budget);
 
             self.flush_actions()?;
             self.write_raw_action(Commands::FINISH.to_string())?;
 
             self.flush()?;
+            budget.end_turn();
+            result?;
         }
         Ok(())
     }
@@ -87,7 +285,10 @@ impl Environment {
         let mut line = String::new();
         match self.reader.read_line(&mut line) {
             Ok(0) => Err(LuxAiError::EmptyInput),
-            Ok(_) => Ok(line),
+            Ok(_) => {
+                self.line += 1;
+                Ok(line)
+            },
             Err(err) => Err(LuxAiError::InputOutput(err)),
         }
     }
@@ -107,6 +308,18 @@ impl Environment {
         Ok(command)
     }
 
+    /// Returns count of lines read from Lux AI API I/O, i.e. number of last
+    /// read line
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// Line number, `0` if nothing was read
+    pub fn line(&self) -> usize { self.line }
+
     /// Reads & parse `Command` from Lux AI API I/O with length constraint
     ///
     /// # Parameters
@@ -135,17 +348,65 @@ impl Environment {
     /// Nothing
     pub fn write_action(&mut self, action: Action) { self.actions.push(action); }
 
-    /// Writes raw `Action` to Lux AI API I/O
+    /// Returns actions cache, i.e. actions queued to be flushed
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// Slice of queued `Action`
+    pub fn actions(&self) -> &[Action] { &self.actions }
+
+    /// Returns mutable actions cache, to filter or reorder queued actions
+    /// before flush
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - mutable Self reference
+    ///
+    /// # Returns
+    ///
+    /// Mutable reference to queued `Action` list
+    pub fn actions_mut(&mut self) -> &mut Vec<Action> { &mut self.actions }
+
+    /// Validates actions cache against `agent` state and removes actions
+    /// referee would drop
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - mutable Self reference
+    /// - `agent` - `Agent` reference with state of current turn
+    ///
+    /// # Returns
+    ///
+    /// Removed `Action` list along with `ActionRejection` reasons
+    pub fn validate_actions(&mut self, agent: &Agent) -> Vec<(Action, ActionRejection)> {
+        let mut validator = ActionValidator::from_agent(agent);
+        let mut rejected = vec![];
+        self.actions
+            .retain(|action| match validator.validate(action) {
+                Ok(()) => true,
+                Err(rejection) => {
+                    rejected.push((action.clone(), rejection));
+                    false
+                },
+            });
+        rejected
+    }
+
+    /// Writes raw action line to Lux AI API I/O
     ///
     /// # Parameters
     ///
     /// - `self` - mutable Self reference
-    /// - `action` - Action to write
+    /// - `action` - raw action line to write
     ///
     /// # Returns
     ///
     /// Nothing or I/O error
-    pub fn write_raw_action(&mut self, action: Action) -> LuxAiResult {
+    pub fn write_raw_action(&mut self, action: String) -> LuxAiResult {
         self.dump_raw_action(action)
             .map_err(|err| LuxAiError::InputOutput(err))
     }
@@ -179,7 +440,7 @@ impl Environment {
             .map_err(|err| LuxAiError::InputOutput(err))
     }
 
-    fn dump_raw_action(&mut self, action: Action) -> io::Result<()> {
+    fn dump_raw_action(&mut self, action: String) -> io::Result<()> {
         writeln!(self.writer, "{}", action)?;
         Ok(())
     }
@@ -196,3 +457,107 @@ impl Environment {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, io, rc::Rc};
+
+    use super::*;
+
+    const TURN_INPUT: &str = concat!(
+        "0\n",
+        "12 12\n",
+        "rp 0 50\n",
+        "weather rain 3\n",
+        "D_DONE\n"
+    );
+
+    fn environment(policy: UnknownCommandPolicy) -> Environment<io::Cursor<&'static str>, Vec<u8>> {
+        let mut environment = Environment::with_io(io::Cursor::new(TURN_INPUT), Vec::new());
+        environment.set_unknown_command_policy(policy);
+        environment
+    }
+
+    fn weather() -> Command { Command::new("weather rain 3".to_string()) }
+
+    #[test]
+    fn unknown_command_is_skipped_by_default() {
+        let mut environment = environment(UnknownCommandPolicy::default());
+        let mut agent = Agent::new(&mut environment).unwrap();
+        agent.update_turn(&mut environment).unwrap();
+
+        assert_eq!(
+            environment.unknown_command_policy(),
+            UnknownCommandPolicy::Ignore
+        );
+        assert!(agent.unknown_commands.is_empty());
+        assert_eq!(agent.players[0].research_points, 50);
+    }
+
+    #[test]
+    fn unknown_command_is_skipped_with_warning() {
+        let mut environment = environment(UnknownCommandPolicy::Warn);
+        let mut agent = Agent::new(&mut environment).unwrap();
+        agent.update_turn(&mut environment).unwrap();
+
+        assert!(agent.unknown_commands.is_empty());
+    }
+
+    #[test]
+    fn unknown_command_is_collected() {
+        let mut environment = environment(UnknownCommandPolicy::Collect);
+        let mut agent = Agent::new(&mut environment).unwrap();
+        agent.update_turn(&mut environment).unwrap();
+
+        assert_eq!(agent.unknown_commands, vec![weather()]);
+    }
+
+    #[test]
+    fn unknown_command_fails_turn() {
+        let mut environment = environment(UnknownCommandPolicy::Fail);
+        let mut agent = Agent::new(&mut environment).unwrap();
+
+        match agent.update_turn(&mut environment) {
+            Err(LuxAiError::UnknownCommand(line, turn, arguments)) => {
+                assert_eq!((line, turn), (4, 1));
+                assert_eq!(arguments, weather().arguments);
+            },
+            result => panic!("unexpected result {:?}", result),
+        }
+    }
+
+    #[test]
+    fn unknown_command_is_passed_to_handler() {
+        let mut environment = environment(UnknownCommandPolicy::Fail);
+        let handled = Rc::new(RefCell::new(vec![]));
+        let commands = handled.clone();
+        environment.register_command_handler("weather", move |agent, command| {
+            commands.borrow_mut().push((agent.turn, command.clone()));
+            Ok(())
+        });
+        environment.register_command_handler(Commands::RESEARCH_POINTS, |_, _| {
+            panic!("protocol command passed to handler")
+        });
+        let mut agent = Agent::new(&mut environment).unwrap();
+        agent.update_turn(&mut environment).unwrap();
+
+        assert_eq!(*handled.borrow(), vec![(1, weather())]);
+        assert!(agent.unknown_commands.is_empty());
+        assert_eq!(agent.players[0].research_points, 50);
+    }
+
+    #[test]
+    fn handler_error_fails_turn() {
+        let mut environment = environment(UnknownCommandPolicy::Ignore);
+        environment.register_command_handler("weather", |_, command| {
+            command.argument::<u32>(1)?;
+            Ok(())
+        });
+        let mut agent = Agent::new(&mut environment).unwrap();
+
+        assert!(matches!(
+            agent.update_turn(&mut environment),
+            Err(LuxAiError::CommandFormat(_))
+        ));
+    }
+}
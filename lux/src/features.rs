@@ -0,0 +1,403 @@
+use std::fmt;
+
+use crate::*;
+
+/// Width and height of [`ObservationTensor`] grid, smaller maps are padded
+pub const FEATURE_GRID_SIZE: Coordinate = 32;
+
+/// Channel of [`ObservationTensor`], "own" and "enemy" are relative to
+/// [`Agent::team`]
+#[derive(Eq, PartialEq, Clone, Copy, fmt::Debug, Hash)]
+pub enum FeatureChannel {
+    /// Wood amount divided by `MAX_WOOD_AMOUNT`, up to `1`
+    Wood,
+    /// Coal amount divided by `MAX_WOOD_AMOUNT`, up to `1`. Referee has no
+    /// limit of coal amount, so all resources share the only documented one
+    Coal,
+    /// Uranium amount divided by `MAX_WOOD_AMOUNT`, up to `1`
+    Uranium,
+    /// Count of own workers on cell
+    OwnWorkers,
+    /// Count of own carts on cell
+    OwnCarts,
+    /// Count of enemy workers on cell
+    EnemyWorkers,
+    /// Count of enemy carts on cell
+    EnemyCarts,
+    /// Sum of own units cargo space used divided by their capacity
+    OwnCargo,
+    /// Sum of enemy units cargo space used divided by their capacity
+    EnemyCargo,
+    /// Max own unit cooldown divided by max `UNIT_ACTION_COOLDOWN`
+    OwnCooldown,
+    /// Max enemy unit cooldown divided by max `UNIT_ACTION_COOLDOWN`
+    EnemyCooldown,
+    /// `1` on own city tile
+    OwnCityTiles,
+    /// `1` on enemy city tile
+    EnemyCityTiles,
+    /// Own city fuel divided by its upkeep of whole night, up to `1`
+    OwnCityFuel,
+    /// Enemy city fuel divided by its upkeep of whole night, up to `1`
+    EnemyCityFuel,
+    /// Road development progress divided by `MAX_ROAD`
+    Roads,
+    /// `1` on all cells at night
+    Night,
+    /// Turn inside day and night cycle divided by cycle length on all cells
+    CyclePhase,
+    /// `1` on cells of the map, `0` on padding
+    OnMap,
+}
+
+impl FeatureChannel {
+    /// Contains all channels in order of [`ObservationTensor`] layout
+    pub const VALUES: [Self; 19] = [
+        Self::Wood,
+        Self::Coal,
+        Self::Uranium,
+        Self::OwnWorkers,
+        Self::OwnCarts,
+        Self::EnemyWorkers,
+        Self::EnemyCarts,
+        Self::OwnCargo,
+        Self::EnemyCargo,
+        Self::OwnCooldown,
+        Self::EnemyCooldown,
+        Self::OwnCityTiles,
+        Self::EnemyCityTiles,
+        Self::OwnCityFuel,
+        Self::EnemyCityFuel,
+        Self::Roads,
+        Self::Night,
+        Self::CyclePhase,
+        Self::OnMap,
+    ];
+}
+
+/// Fixed-shape multi-channel grid encoding of [`Agent`] state for ML
+/// policies
+///
+/// Values are stored flat in channel-major `[channel][y][x]` order, see
+/// [`FeatureChannel::VALUES`] for channels order. Map is placed at top-left
+/// corner of [`FEATURE_GRID_SIZE`] x [`FEATURE_GRID_SIZE`] grid, padding cells
+/// are zeros
+///
+/// # Examples
+///
+/// ```
+/// # use lux_ai_api::*;
+/// # let agent = MapGenerator::new(1, 12)?.generate().to_agent(0);
+/// # let unit = &agent.player().units[0];
+/// let tensor = ObservationTensor::from_agent(&agent);
+/// let wood = tensor.get(FeatureChannel::Wood, unit.pos);
+/// let input: Vec<f32> = tensor.into_vec();
+/// # Ok::<(), LuxAiError>(())
+/// ```
+#[derive(Clone, PartialEq, fmt::Debug)]
+pub struct ObservationTensor {
+    data: Vec<f32>,
+}
+
+impl ObservationTensor {
+    /// Returns shape of tensor
+    ///
+    /// # Parameters
+    ///
+    /// None
+    ///
+    /// # Returns
+    ///
+    /// `[channels, height, width]` array
+    pub fn shape() -> [usize; 3] {
+        let size = FEATURE_GRID_SIZE as usize;
+        [FeatureChannel::VALUES.len(), size, size]
+    }
+
+    /// Encodes [`Agent`] state from its team point of view
+    ///
+    /// # Parameters
+    ///
+    /// - `agent` - [`Agent`] reference
+    ///
+    /// # Returns
+    ///
+    /// A new created [`ObservationTensor`]
+    pub fn from_agent(agent: &Agent) -> Self {
+        let [channels, height, width] = Self::shape();
+        let mut tensor = Self {
+            data: vec![0.0; channels * height * width],
+        };
+        let parameters = &GAME_CONSTANTS.parameters;
+        let clock = agent.clock();
+        let max_cooldown = parameters
+            .unit_action_cooldown
+            .values()
+            .copied()
+            .max()
+            .unwrap_or(1) as f32;
+
+        for cell in agent.game_map.map.iter().flatten() {
+            if !Self::contains(cell.pos) {
+                continue;
+            }
+            tensor.set(FeatureChannel::OnMap, cell.pos, 1.0);
+            tensor.set(
+                FeatureChannel::Roads,
+                cell.pos,
+                cell.road / parameters.max_road,
+            );
+            tensor.set(
+                FeatureChannel::Night,
+                cell.pos,
+                if clock.is_night() { 1.0 } else { 0.0 },
+            );
+            tensor.set(
+                FeatureChannel::CyclePhase,
+                cell.pos,
+                clock.cycle_turn() as f32 / GameClock::cycle_length() as f32,
+            );
+            if let Some(resource) = cell.resource.as_ref() {
+                let channel = match resource.resource_type {
+                    ResourceType::Wood => FeatureChannel::Wood,
+                    ResourceType::Coal => FeatureChannel::Coal,
+                    ResourceType::Uranium => FeatureChannel::Uranium,
+                };
+                let amount = resource.amount as f32 / parameters.max_wood_amount as f32;
+                tensor.set(channel, cell.pos, amount.min(1.0));
+            }
+        }
+
+        for player in agent.players.iter() {
+            let own = player.team == agent.team;
+            for unit in player.units.iter().filter(|unit| Self::contains(unit.pos)) {
+                let count_channel = match (own, unit.unit_type) {
+                    (true, UnitType::Worker) => FeatureChannel::OwnWorkers,
+                    (true, UnitType::Cart) => FeatureChannel::OwnCarts,
+                    (false, UnitType::Worker) => FeatureChannel::EnemyWorkers,
+                    (false, UnitType::Cart) => FeatureChannel::EnemyCarts,
+                };
+                let (cargo_channel, cooldown_channel) = match own {
+                    true => (FeatureChannel::OwnCargo, FeatureChannel::OwnCooldown),
+                    false => (FeatureChannel::EnemyCargo, FeatureChannel::EnemyCooldown),
+                };
+                let cargo =
+                    unit.cargo_space_used() as f32 / unit.unit_type.cargo_space_available() as f32;
+                let cooldown = unit.cooldown / max_cooldown;
+
+                tensor.add(count_channel, unit.pos, 1.0);
+                tensor.add(cargo_channel, unit.pos, cargo);
+                let previous = tensor.get(cooldown_channel, unit.pos);
+                tensor.set(cooldown_channel, unit.pos, previous.max(cooldown));
+            }
+
+            let (tile_channel, fuel_channel) = match own {
+                true => (FeatureChannel::OwnCityTiles, FeatureChannel::OwnCityFuel),
+                false => (
+                    FeatureChannel::EnemyCityTiles,
+                    FeatureChannel::EnemyCityFuel,
+                ),
+            };
+            for city in player.cities.values() {
+                let night_upkeep = city.light_upkeep * parameters.night_length as FuelAmount;
+                let fuel = match night_upkeep > 0.0 {
+                    true => (city.fuel / night_upkeep).min(1.0),
+                    false => 1.0,
+                };
+                for city_tile in city.city_tiles(&agent.game_map) {
+                    let position = city_tile.pos;
+                    if Self::contains(position) {
+                        tensor.set(tile_channel, position, 1.0);
+                        tensor.set(fuel_channel, position, fuel);
+                    }
+                }
+            }
+        }
+        tensor
+    }
+
+    /// Returns value of `channel` at `position`
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    /// - `channel` - [`FeatureChannel`] to read
+    /// - `position` - [`Position`] of cell
+    ///
+    /// # Returns
+    ///
+    /// Value or `0` if `position` is outside of grid
+    pub fn get(&self, channel: FeatureChannel, position: Position) -> f32 {
+        match Self::contains(position) {
+            true => self.data[Self::index(channel, position)],
+            false => 0.0,
+        }
+    }
+
+    /// Returns flat values in channel-major `[channel][y][x]` order
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// Values slice
+    pub fn as_slice(&self) -> &[f32] { &self.data }
+
+    /// Converts into flat values in channel-major `[channel][y][x]` order
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self value
+    ///
+    /// # Returns
+    ///
+    /// Values vector
+    pub fn into_vec(self) -> Vec<f32> { self.data }
+
+    /// Returns index of value of `channel` at `position` in flat values
+    ///
+    /// # Parameters
+    ///
+    /// - `channel` - [`FeatureChannel`] of value
+    /// - `position` - [`Position`] of cell, inside of grid
+    ///
+    /// # Returns
+    ///
+    /// Index in flat values
+    pub fn index(channel: FeatureChannel, position: Position) -> usize {
+        let size = FEATURE_GRID_SIZE as usize;
+        let channel = FeatureChannel::VALUES
+            .iter()
+            .position(|value| *value == channel)
+            .unwrap();
+        (channel * size + position.y as usize) * size + position.x as usize
+    }
+
+    fn contains(position: Position) -> bool {
+        position.x >= 0 &&
+            position.y >= 0 &&
+            position.x < FEATURE_GRID_SIZE &&
+            position.y < FEATURE_GRID_SIZE
+    }
+
+    fn set(&mut self, channel: FeatureChannel, position: Position, value: f32) {
+        self.data[Self::index(channel, position)] = value;
+    }
+
+    fn add(&mut self, channel: FeatureChannel, position: Position, value: f32) {
+        self.data[Self::index(channel, position)] += value;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn agent() -> Agent {
+        let players = vec![Player::new(0), Player::new(1)];
+        let mut simulator = Simulator::new(31, GameMap::new(12, 12), players);
+        let mut worker = Unit::new(
+            0,
+            UnitType::Worker,
+            "u_1".to_string(),
+            Position::new(2, 3),
+            1.0,
+        );
+        worker.cargo.wood = 50;
+        simulator.players[0].units.push(worker);
+        let cart = Unit::new(
+            1,
+            UnitType::Cart,
+            "u_2".to_string(),
+            Position::new(11, 11),
+            3.0,
+        );
+        simulator.players[1].units.push(cart);
+        simulator.game_map[Position::new(0, 1)].resource =
+            Some(Resource::new(ResourceType::Wood, 250));
+        simulator.game_map[Position::new(5, 5)].resource =
+            Some(Resource::new(ResourceType::Coal, 1000));
+        simulator.game_map[Position::new(4, 4)].road = GAME_CONSTANTS.parameters.max_road;
+
+        let mut city = City::new(1, "c_1".to_string(), 115.0, 23.0);
+        city.add_city_tile(&mut simulator.game_map, Position::new(7, 8), 0.0);
+        simulator.players[1]
+            .cities
+            .insert(city.cityid.clone(), city);
+        simulator.to_agent(0)
+    }
+
+    #[test]
+    fn small_map_is_padded() {
+        let tensor = ObservationTensor::from_agent(&agent());
+
+        assert_eq!(
+            ObservationTensor::shape(),
+            [FeatureChannel::VALUES.len(), 32, 32]
+        );
+        assert_eq!(
+            tensor.as_slice().len(),
+            FeatureChannel::VALUES.len() * 32 * 32
+        );
+        assert_eq!(
+            tensor.get(FeatureChannel::OnMap, Position::new(11, 11)),
+            1.0
+        );
+        assert_eq!(tensor.get(FeatureChannel::Night, Position::new(0, 0)), 1.0);
+        for position in [
+            Position::new(12, 0),
+            Position::new(0, 12),
+            Position::new(31, 31),
+        ] {
+            for channel in FeatureChannel::VALUES {
+                assert_eq!(tensor.get(channel, position), 0.0);
+            }
+        }
+        assert_eq!(tensor.get(FeatureChannel::OnMap, Position::new(32, 0)), 0.0);
+    }
+
+    #[test]
+    fn values_are_stored_channel_major() {
+        let tensor = ObservationTensor::from_agent(&agent());
+        let data = tensor.clone().into_vec();
+
+        assert_eq!(
+            ObservationTensor::index(FeatureChannel::Wood, Position::new(0, 0)),
+            0
+        );
+        assert_eq!(
+            ObservationTensor::index(FeatureChannel::Coal, Position::new(1, 2)),
+            32 * 32 + 2 * 32 + 1
+        );
+        for (index, channel) in FeatureChannel::VALUES.into_iter().enumerate() {
+            let position = Position::new(2, 3);
+            assert_eq!(
+                data[(index * 32 + 3) * 32 + 2],
+                tensor.get(channel, position)
+            );
+        }
+    }
+
+    #[test]
+    fn channels_are_encoded_from_team_point_of_view() {
+        let tensor = ObservationTensor::from_agent(&agent());
+        let get = |channel, x, y| tensor.get(channel, Position::new(x, y));
+
+        assert_eq!(get(FeatureChannel::Wood, 0, 1), 0.5);
+        assert_eq!(get(FeatureChannel::Coal, 5, 5), 1.0);
+        assert_eq!(get(FeatureChannel::Uranium, 5, 5), 0.0);
+        assert_eq!(get(FeatureChannel::OwnWorkers, 2, 3), 1.0);
+        assert_eq!(get(FeatureChannel::OwnCargo, 2, 3), 0.5);
+        assert_eq!(get(FeatureChannel::OwnCooldown, 2, 3), 1.0 / 3.0);
+        assert_eq!(get(FeatureChannel::EnemyCarts, 11, 11), 1.0);
+        assert_eq!(get(FeatureChannel::EnemyCooldown, 11, 11), 1.0);
+        assert_eq!(get(FeatureChannel::OwnCarts, 11, 11), 0.0);
+        assert_eq!(get(FeatureChannel::EnemyCityTiles, 7, 8), 1.0);
+        assert_eq!(get(FeatureChannel::EnemyCityFuel, 7, 8), 0.5);
+        assert_eq!(get(FeatureChannel::OwnCityTiles, 7, 8), 0.0);
+        assert_eq!(get(FeatureChannel::Roads, 4, 4), 1.0);
+        assert_eq!(get(FeatureChannel::CyclePhase, 0, 0), 0.75);
+    }
+}
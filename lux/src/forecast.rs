@@ -0,0 +1,393 @@
+use std::fmt;
+
+use crate::*;
+
+/// Projection of whether [`City`] or [`Unit`] survives to the end of next
+/// night, assuming it gains no more fuel
+///
+/// # Examples
+///
+/// ```
+/// # use lux_ai_api::*;
+/// # let agent = MapGenerator::new(1, 12)?.generate().to_agent(0);
+/// for city in agent.player().cities.values() {
+///     let forecast = city.night_forecast(agent.turn);
+///     if !forecast.survives() {
+///         eprintln!("{} needs {} more fuel", city.cityid, forecast.fuel_needed);
+///     }
+/// }
+/// # Ok::<(), LuxAiError>(())
+/// ```
+///
+/// # See also
+///
+/// Check <https://www.lux-ai.org/specs-2021#Day/Night%20Cycle>
+#[derive(Clone, Copy, PartialEq, fmt::Debug)]
+pub struct NightForecast {
+    /// Night turns left until the end of next night, including current turn
+    /// if it is night
+    pub night_turns: TurnAmount,
+
+    /// Night turns current fuel lasts for
+    pub turns_of_fuel: TurnAmount,
+
+    /// Fuel still needed to survive until the end of next night, `0` if
+    /// current fuel is enough
+    pub fuel_needed: FuelAmount,
+}
+
+impl NightForecast {
+    /// Whether or not current fuel is enough to survive until the end of next
+    /// night
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// `bool` value
+    pub fn survives(&self) -> bool { self.turns_of_fuel >= self.night_turns }
+}
+
+/// Projection of when [`Player`] unlocks [`ResourceType`], assuming its city
+/// tiles which are off cooldown research every time their cooldown allows
+///
+/// # Examples
+///
+/// ```
+/// # use lux_ai_api::*;
+/// # let agent = MapGenerator::new(1, 12)?.generate().to_agent(0);
+/// let forecast = agent.player().research_forecast(&agent.game_map, agent.turn, ResourceType::Coal);
+/// if let Some(unlock_turn) = forecast.unlock_turn {
+///     eprintln!("Coal unlocks on turn {}", unlock_turn);
+/// }
+/// # Ok::<(), LuxAiError>(())
+/// ```
+///
+/// # See also
+///
+/// Check <https://www.lux-ai.org/specs-2021#Resources>
+#[derive(Clone, Copy, PartialEq, fmt::Debug)]
+pub struct ResearchForecast {
+    /// Resource type being researched
+    pub resource_type: ResourceType,
+
+    /// Research points still needed, `0` if already researched
+    pub points_needed: ResearchPointAmount,
+
+    /// City tiles off cooldown, which are expected to research
+    pub researching_tiles: u32,
+
+    /// Turns of research until resource is unlocked, including current turn,
+    /// `None` if no city tile can research
+    pub turns_until_unlocked: Option<TurnAmount>,
+
+    /// First turn on which resource is unlocked, `None` if no city tile can
+    /// research
+    pub unlock_turn: Option<TurnAmount>,
+}
+
+impl ResearchForecast {
+    /// Whether or not resource is already unlocked
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// `bool` value
+    pub fn is_unlocked(&self) -> bool { self.points_needed == 0 }
+
+    /// Returns turns needed by `tiles` city tiles to gain `points` research
+    /// points, each of them researches once per city action cooldown
+    pub(crate) fn turns_to_research(points: ResearchPointAmount, tiles: u32) -> Option<TurnAmount> {
+        if points <= 0 {
+            return Some(0);
+        }
+        if tiles == 0 {
+            return None;
+        }
+        let period = GAME_CONSTANTS.parameters.city_action_cooldown;
+        let actions = (points as u32).div_ceil(tiles) as TurnAmount;
+        Some((actions - 1) * period + 1)
+    }
+
+    /// Returns min count of city tiles gaining `points` research points in
+    /// `turns` turns
+    pub(crate) fn tiles_to_research(points: ResearchPointAmount, turns: TurnAmount) -> Option<u32> {
+        if points <= 0 {
+            return Some(0);
+        }
+        if turns <= 0 {
+            return None;
+        }
+        let period = GAME_CONSTANTS.parameters.city_action_cooldown;
+        let actions_per_tile = ((turns - 1) / period + 1) as u32;
+        Some((points as u32).div_ceil(actions_per_tile))
+    }
+}
+
+/// Predicted [`City`] after building [`CityTile`] at some position, new tile
+/// joins first adjacent city and all other adjacent cities merge into it
+///
+/// # Examples
+///
+/// ```
+/// # use lux_ai_api::*;
+/// # let agent = MapGenerator::new(1, 12)?.generate().to_agent(0);
+/// # let position = agent.player().units[0].pos;
+/// let prediction = agent.player().predict_city_build(&agent.game_map, position);
+/// if !prediction.night_forecast(agent.turn).survives() {
+///     // building here starves the city
+/// }
+/// # Ok::<(), LuxAiError>(())
+/// ```
+///
+/// # See also
+///
+/// Check <https://www.lux-ai.org/specs-2021#CityTiles>
+#[derive(Clone, PartialEq, fmt::Debug)]
+pub struct CityBuildPrediction {
+    /// Team id of building player
+    pub team: TeamId,
+
+    /// Id of city new tile joins, `None` if it founds new city
+    pub city_id: Option<EntityId>,
+
+    /// Ids of cities merged into city with `city_id`
+    pub merged_city_ids: Vec<EntityId>,
+
+    /// Count of city tiles of resulting city, including new tile
+    pub city_tiles: usize,
+
+    /// Fuel of resulting city
+    pub fuel: FuelAmount,
+
+    /// Light upkeep of resulting city
+    pub light_upkeep: FuelAmount,
+}
+
+impl CityBuildPrediction {
+    /// Projects whether resulting city survives to the end of next night with
+    /// its current fuel
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    /// - `turn` - turn index, counted the same way as [`Agent::turn`]
+    ///
+    /// # Returns
+    ///
+    /// [`NightForecast`] of resulting city
+    pub fn night_forecast(&self, turn: TurnAmount) -> NightForecast {
+        let city_id = self.city_id.clone().unwrap_or_default();
+        City::new(self.team, city_id, self.fuel, self.light_upkeep).night_forecast(turn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn worker(wood: ResourceAmount, coal: ResourceAmount) -> Unit {
+        let mut unit = Unit::new(
+            0,
+            UnitType::Worker,
+            "u_1".to_string(),
+            Position::new(0, 0),
+            0.0,
+        );
+        unit.cargo.wood = wood;
+        unit.cargo.coal = coal;
+        unit
+    }
+
+    #[test]
+    fn night_turns_at_day_night_boundaries() {
+        assert_eq!(GameClock::new(1).night_turns_left(), 10);
+        assert_eq!(GameClock::new(30).night_turns_left(), 10);
+        assert_eq!(GameClock::new(31).night_turns_left(), 10);
+        assert_eq!(GameClock::new(35).night_turns_left(), 6);
+        assert_eq!(GameClock::new(40).night_turns_left(), 1);
+        assert_eq!(GameClock::new(41).night_turns_left(), 10);
+        assert_eq!(GameClock::new(360).night_turns_left(), 1);
+    }
+
+    #[test]
+    fn city_forecast() {
+        let city = City::new(0, "c_1".to_string(), 100.0, 23.0);
+
+        let forecast = city.night_forecast(1);
+        assert_eq!(
+            forecast,
+            NightForecast {
+                night_turns:   10,
+                turns_of_fuel: 4,
+                fuel_needed:   130.0,
+            }
+        );
+        assert!(!forecast.survives());
+
+        let forecast = city.night_forecast(38);
+        assert_eq!(forecast.night_turns, 3);
+        assert_eq!(forecast.fuel_needed, 0.0);
+        assert!(forecast.survives());
+
+        let city = City::new(0, "c_2".to_string(), 0.0, 0.0);
+        assert!(city.night_forecast(31).survives());
+    }
+
+    #[test]
+    fn unit_forecast() {
+        let forecast = worker(20, 0).night_forecast(1);
+        assert_eq!(
+            forecast,
+            NightForecast {
+                night_turns:   10,
+                turns_of_fuel: 5,
+                fuel_needed:   20.0,
+            }
+        );
+        assert!(!forecast.survives());
+
+        let forecast = worker(2, 1).night_forecast(31);
+        assert_eq!(forecast.turns_of_fuel, 1);
+        assert_eq!(forecast.fuel_needed, 36.0);
+
+        let forecast = worker(4, 0).night_forecast(40);
+        assert_eq!(forecast.night_turns, 1);
+        assert!(forecast.survives());
+    }
+
+    #[test]
+    fn turns_to_research() {
+        assert_eq!(ResearchForecast::turns_to_research(0, 0), Some(0));
+        assert_eq!(ResearchForecast::turns_to_research(-5, 3), Some(0));
+        assert_eq!(ResearchForecast::turns_to_research(5, 0), None);
+        assert_eq!(ResearchForecast::turns_to_research(1, 1), Some(1));
+        assert_eq!(ResearchForecast::turns_to_research(50, 1), Some(491));
+        assert_eq!(ResearchForecast::turns_to_research(50, 5), Some(91));
+        assert_eq!(ResearchForecast::turns_to_research(51, 5), Some(101));
+    }
+
+    #[test]
+    fn tiles_to_research() {
+        assert_eq!(ResearchForecast::tiles_to_research(0, 0), Some(0));
+        assert_eq!(ResearchForecast::tiles_to_research(5, 0), None);
+        assert_eq!(ResearchForecast::tiles_to_research(50, 1), Some(50));
+        assert_eq!(ResearchForecast::tiles_to_research(50, 10), Some(50));
+        assert_eq!(ResearchForecast::tiles_to_research(50, 11), Some(25));
+        assert_eq!(ResearchForecast::tiles_to_research(50, 91), Some(5));
+
+        for points in 1..60 {
+            for tiles in 1..12 {
+                let turns = ResearchForecast::turns_to_research(points, tiles).unwrap();
+                let needed = ResearchForecast::tiles_to_research(points, turns).unwrap();
+                assert!(needed <= tiles, "{} points in {} turns", points, turns);
+            }
+        }
+    }
+
+    #[test]
+    fn player_research_forecast() {
+        let mut game_map = GameMap::new(4, 4);
+        let mut player = Player::new(0);
+        player.research_points = 40;
+        let mut city = City::new(0, "c_1".to_string(), 0.0, 0.0);
+        city.add_city_tile(&mut game_map, Position::new(0, 0), 0.0);
+        city.add_city_tile(&mut game_map, Position::new(1, 0), 0.0);
+        city.add_city_tile(&mut game_map, Position::new(2, 0), 10.0);
+        player.cities.insert(city.cityid.clone(), city);
+
+        let forecast = player.research_forecast(&game_map, 5, ResourceType::Coal);
+        assert_eq!(
+            forecast,
+            ResearchForecast {
+                resource_type:        ResourceType::Coal,
+                points_needed:        10,
+                researching_tiles:    2,
+                turns_until_unlocked: Some(41),
+                unlock_turn:          Some(46),
+            }
+        );
+        assert!(!forecast.is_unlocked());
+        assert!(player
+            .research_forecast(&game_map, 5, ResourceType::Wood)
+            .is_unlocked());
+
+        assert_eq!(
+            player.research_tiles_needed(&game_map, 5, ResourceType::Coal, 46),
+            Some(2)
+        );
+        assert_eq!(
+            player.research_tiles_needed(&game_map, 5, ResourceType::Coal, 36),
+            None
+        );
+        assert_eq!(
+            player.research_tiles_needed(&game_map, 5, ResourceType::Coal, 96),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn tile_light_upkeep_is_reduced_by_adjacent_tiles() {
+        let upkeep: Vec<FuelAmount> = (0..=4).map(City::tile_light_upkeep).collect();
+        assert_eq!(upkeep, vec![23.0, 18.0, 13.0, 8.0, 3.0]);
+    }
+
+    #[test]
+    fn light_upkeep_counts_only_friendly_adjacent_tiles() {
+        let mut game_map = GameMap::new(4, 4);
+        let mut city = City::new(0, "c_1".to_string(), 0.0, 0.0);
+        for (x, y) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+            city.add_city_tile(&mut game_map, Position::new(x, y), 0.0);
+        }
+        let mut enemy = City::new(1, "c_2".to_string(), 0.0, 0.0);
+        enemy.add_city_tile(&mut game_map, Position::new(2, 0), 0.0);
+
+        assert_eq!(
+            City::adjacent_city_tiles(&game_map, 0, Position::new(1, 0)),
+            2
+        );
+        assert_eq!(
+            City::adjacent_city_tiles(&game_map, 1, Position::new(1, 0)),
+            1
+        );
+        assert_eq!(city.compute_light_upkeep(&game_map), 4.0 * 13.0);
+        assert_eq!(enemy.compute_light_upkeep(&game_map), 23.0);
+    }
+
+    #[test]
+    fn city_build_prediction() {
+        let mut game_map = GameMap::new(5, 5);
+        let mut player = Player::new(0);
+        for (id, x, fuel) in [("c_1", 1, 40.0), ("c_2", 3, 60.0)] {
+            let mut city = City::new(0, id.to_string(), fuel, 0.0);
+            city.add_city_tile(&mut game_map, Position::new(x, 1), 0.0);
+            city.light_upkeep = city.compute_light_upkeep(&game_map);
+            player.cities.insert(city.cityid.clone(), city);
+        }
+
+        let prediction = player.predict_city_build(&game_map, Position::new(2, 1));
+        assert_eq!(
+            prediction,
+            CityBuildPrediction {
+                team:            0,
+                city_id:         Some("c_1".to_string()),
+                merged_city_ids: vec!["c_2".to_string()],
+                city_tiles:      3,
+                fuel:            100.0,
+                light_upkeep:    13.0 + 2.0 * 18.0,
+            }
+        );
+
+        let prediction = player.predict_city_build(&game_map, Position::new(3, 3));
+        assert_eq!(prediction.city_id, None);
+        assert!(prediction.merged_city_ids.is_empty());
+        assert_eq!((prediction.city_tiles, prediction.fuel), (1, 0.0));
+        assert_eq!(prediction.light_upkeep, 23.0);
+        assert!(!prediction.night_forecast(1).survives());
+    }
+}
@@ -1,17 +1,36 @@
+pub mod actions;
 pub mod agent;
 pub mod amounts;
 pub mod annotate;
+pub mod budget;
+pub mod clock;
+pub mod clusters;
 pub mod commands;
+pub mod compact;
+pub mod coordinator;
 pub mod entities;
 pub mod environment;
+pub mod features;
+pub mod forecast;
 pub mod game_constants;
-
-use std::{fmt, io, result};
+pub mod map_generator;
+pub mod mcts;
+pub mod pathfinding;
+pub mod replay;
+pub mod simulator;
+pub mod snapshot;
+pub mod spatial;
+pub mod validator;
+pub mod world;
+
+use std::{fmt, io, result, str};
 
 use serde::{Deserialize, Serialize};
 
-pub use self::{agent::*, amounts::*, annotate::*, commands::*, entities::*, environment::*,
-               game_constants::*};
+pub use self::{actions::*, agent::*, amounts::*, annotate::*, budget::*, clock::*, clusters::*,
+               commands::*, compact::*, coordinator::*, entities::*, environment::*, features::*,
+               forecast::*, game_constants::*, map_generator::*, mcts::*, pathfinding::*,
+               replay::*, simulator::*, snapshot::*, spatial::*, validator::*, world::*};
 
 /// Count of teams participating in match
 pub const TEAM_COUNT: TeamId = 2;
@@ -27,6 +46,14 @@ pub enum LuxAiError {
     #[error("Command format error: {0:?}")]
     CommandFormat(Vec<String>),
 
+    /// Malformed line of input, with its location
+    #[error("Input format error: {0}")]
+    InputFormat(#[from] InputFormatError),
+
+    /// Input command not defined by protocol, with line number and turn
+    #[error("Unknown command at line {0}, turn {1}: {2:?}")]
+    UnknownCommand(usize, TurnAmount, Vec<String>),
+
     /// City not exists, Command semantic error
     #[error("City not exists: {0}")]
     CityNotExists(String),
@@ -43,6 +70,26 @@ pub enum LuxAiError {
     #[error("Unknown unit: {0}")]
     UnknownUnit(String),
 
+    /// Direction not exists, Command semantic error
+    #[error("Unknown direction: {0}")]
+    UnknownDirection(String),
+
+    /// Map size not supported by map generator
+    #[error("Unsupported map size: {0}")]
+    UnsupportedMapSize(Coordinate),
+
+    /// JSON parsing error
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// Replay file has unexpected structure
+    #[error("Replay format error: {0}")]
+    ReplayFormat(String),
+
+    /// Snapshot describes state that can not exist on its map
+    #[error("Snapshot format error: {0}")]
+    SnapshotFormat(String),
+
     /// Empty input, to handle end of match
     #[error("Empty input error")]
     EmptyInput,
@@ -98,3 +145,17 @@ impl Direction {
     /// `GAME_CONSTANTS.directions`
     pub fn to_argument(&self) -> String { GAME_CONSTANTS.directions[self].clone() }
 }
+
+/// Convert from command argument into `Direction`
+impl str::FromStr for Direction {
+    type Err = LuxAiError;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        GAME_CONSTANTS
+            .directions
+            .iter()
+            .find(|(_, argument)| argument.as_str() == string)
+            .map(|(direction, _)| *direction)
+            .ok_or_else(|| Self::Err::UnknownDirection(string.to_string()))
+    }
+}
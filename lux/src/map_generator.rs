@@ -0,0 +1,458 @@
+use std::fmt;
+
+use crate::*;
+
+/// Map sizes supported by official generator
+pub const MAP_SIZES: [Coordinate; 4] = [12, 16, 24, 32];
+
+/// Deterministic pseudo random numbers generator (SplitMix64), the same seed
+/// always produces the same sequence
+#[derive(Clone, fmt::Debug)]
+pub struct SeededRandom {
+    state: u64,
+}
+
+impl SeededRandom {
+    /// Creates [`SeededRandom`] with given `seed`
+    ///
+    /// # Parameters
+    ///
+    /// - `seed` - initial state
+    ///
+    /// # Returns
+    ///
+    /// A new created [`SeededRandom`]
+    pub fn new(seed: u64) -> Self { Self { state: seed } }
+
+    /// Returns next value of sequence
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - mutable Self reference
+    ///
+    /// # Returns
+    ///
+    /// `u64` value
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut value = self.state;
+        value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        value ^ (value >> 31)
+    }
+
+    /// Returns next value in `[0, 1)` range
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - mutable Self reference
+    ///
+    /// # Returns
+    ///
+    /// `f32` value
+    pub fn next_f32(&mut self) -> f32 { (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32 }
+
+    /// Returns next value in `[low, high)` range
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - mutable Self reference
+    /// - `low` - inclusive lower bound
+    /// - `high` - exclusive upper bound, `low` is returned if not greater than
+    ///   `low`
+    ///
+    /// # Returns
+    ///
+    /// `i32` value
+    pub fn range(&mut self, low: i32, high: i32) -> i32 {
+        low + (self.next_u64() % (high - low).max(1) as u64) as i32
+    }
+}
+
+/// Symmetry of generated map, both teams start at mirrored positions
+#[derive(Eq, PartialEq, Clone, Copy, fmt::Debug)]
+pub enum MapSymmetry {
+    /// Left half mirrored into right half, `x` is reflected
+    Horizontal,
+    /// Top half mirrored into bottom half, `y` is reflected
+    Vertical,
+}
+
+impl MapSymmetry {
+    /// Returns [`Position`] mirrored by this symmetry
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    /// - `position` - [`Position`] to mirror
+    /// - `size` - width and height of map
+    ///
+    /// # Returns
+    ///
+    /// Mirrored [`Position`]
+    pub fn mirror(&self, position: Position, size: Coordinate) -> Position {
+        match self {
+            Self::Horizontal => Position::new(size - 1 - position.x, position.y),
+            Self::Vertical => Position::new(position.x, size - 1 - position.y),
+        }
+    }
+}
+
+/// Parameters of one resource type clusters generation with cellular automaton
+struct ClusterRule {
+    resource_type: ResourceType,
+    fill_rate:     f32,
+    birth_limit:   usize,
+    death_limit:   usize,
+    steps:         usize,
+    min_amount:    ResourceAmount,
+    max_amount:    ResourceAmount,
+}
+
+/// Cluster rules, applied in order, later resources never overwrite earlier
+const CLUSTER_RULES: [ClusterRule; 3] = [
+    ClusterRule {
+        resource_type: ResourceType::Wood,
+        fill_rate:     0.21,
+        birth_limit:   4,
+        death_limit:   2,
+        steps:         3,
+        min_amount:    300,
+        max_amount:    500,
+    },
+    ClusterRule {
+        resource_type: ResourceType::Coal,
+        fill_rate:     0.11,
+        birth_limit:   5,
+        death_limit:   2,
+        steps:         2,
+        min_amount:    350,
+        max_amount:    450,
+    },
+    ClusterRule {
+        resource_type: ResourceType::Uranium,
+        fill_rate:     0.055,
+        birth_limit:   6,
+        death_limit:   1,
+        steps:         2,
+        min_amount:    300,
+        max_amount:    350,
+    },
+];
+
+/// Minimal share of wood cells on generated map, maps with less wood are
+/// rejected and generated again
+const MIN_WOOD_SHARE: f32 = 0.05;
+
+/// Deterministic seeded map generator following official generator layout
+/// rules
+///
+/// Compatibility with official generator is limited to these layout rules, it
+/// is not a port of official generator:
+///
+/// - map is square with one of [`MAP_SIZES`] sizes
+/// - resources are generated on one half of the map and mirrored into another
+///   one, either horizontally or vertically
+/// - wood, coal and uranium form clusters grown by cellular automaton
+/// - each team starts with one [`CityTile`] and one worker on it, placed next
+///   to wood at mirrored positions
+///
+/// Pseudo random numbers and cluster growth differ from official generator,
+/// so the same seed always gives the same map, but not the map official
+/// generator gives for this seed
+///
+/// # Examples
+///
+/// ```
+/// # use lux_ai_api::*;
+/// let simulator = MapGenerator::new(42, 16)?.generate();
+/// let agent = simulator.to_agent(0);
+/// # Ok::<(), LuxAiError>(())
+/// ```
+///
+/// # See also
+///
+/// Check <https://www.lux-ai.org/specs-2021#The%20Map>
+#[derive(Clone, fmt::Debug)]
+pub struct MapGenerator {
+    /// Seed of pseudo random numbers generator
+    pub seed: u64,
+
+    /// Width and height of generated map
+    pub size: Coordinate,
+}
+
+impl MapGenerator {
+    /// Creates [`MapGenerator`] with given `seed` and `size`
+    ///
+    /// # Parameters
+    ///
+    /// - `seed` - seed of pseudo random numbers generator
+    /// - `size` - width and height of map, one of [`MAP_SIZES`]
+    ///
+    /// # Returns
+    ///
+    /// A new created [`MapGenerator`] or error if `size` is not supported
+    pub fn new(seed: u64, size: Coordinate) -> LuxAiResult<Self> {
+        if !MAP_SIZES.contains(&size) {
+            return Err(LuxAiError::UnsupportedMapSize(size));
+        }
+        Ok(Self { seed, size })
+    }
+
+    /// Generates initial state of match
+    ///
+    /// Same `seed` and `size` always produce the same state
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// [`Simulator`] with generated [`GameMap`] and [`players`][Player] at
+    /// first turn
+    pub fn generate(&self) -> Simulator {
+        let mut random = SeededRandom::new(self.seed);
+        loop {
+            let symmetry = if random.next_f32() < 0.5 {
+                MapSymmetry::Horizontal
+            } else {
+                MapSymmetry::Vertical
+            };
+            let mut game_map = GameMap::new(self.size, self.size);
+            self.generate_resources(&mut random, symmetry, &mut game_map);
+            if !self.has_enough_wood(&game_map) {
+                continue;
+            }
+            if let Some(spawn) = self.find_spawn(&mut random, symmetry, &game_map) {
+                let players = self.spawn_players(symmetry, spawn, &mut game_map);
+                return Simulator::new(1, game_map, players);
+            }
+        }
+    }
+
+    fn half_dimensions(&self, symmetry: MapSymmetry) -> (Coordinate, Coordinate) {
+        match symmetry {
+            MapSymmetry::Horizontal => (self.size / 2, self.size),
+            MapSymmetry::Vertical => (self.size, self.size / 2),
+        }
+    }
+
+    fn generate_resources(
+        &self, random: &mut SeededRandom, symmetry: MapSymmetry, game_map: &mut GameMap,
+    ) {
+        let (half_width, half_height) = self.half_dimensions(symmetry);
+        for rule in CLUSTER_RULES.iter() {
+            let alive = Self::grow_clusters(random, rule, half_width, half_height);
+            for y in 0..half_height {
+                for x in 0..half_width {
+                    let position = Position::new(x, y);
+                    if !alive[y as usize][x as usize] || game_map[position].resource.is_some() {
+                        continue;
+                    }
+                    let amount = random.range(rule.min_amount, rule.max_amount + 1);
+                    let resource = Resource::new(rule.resource_type, amount);
+                    game_map[symmetry.mirror(position, self.size)].resource =
+                        Some(resource.clone());
+                    game_map[position].resource = Some(resource);
+                }
+            }
+        }
+    }
+
+    fn grow_clusters(
+        random: &mut SeededRandom, rule: &ClusterRule, width: Coordinate, height: Coordinate,
+    ) -> Vec<Vec<bool>> {
+        let (width, height) = (width as usize, height as usize);
+        let mut alive: Vec<Vec<bool>> = (0..height)
+            .map(|_| {
+                (0..width)
+                    .map(|_| random.next_f32() < rule.fill_rate)
+                    .collect()
+            })
+            .collect();
+
+        for _ in 0..rule.steps {
+            let previous = alive.clone();
+            for y in 0..height {
+                for x in 0..width {
+                    let neighbors = (-1..=1)
+                        .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+                        .filter(|&(dx, dy)| (dx, dy) != (0, 0))
+                        .filter(|&(dx, dy)| {
+                            let (nx, ny) = (x as i32 + dx, y as i32 + dy);
+                            nx >= 0 &&
+                                ny >= 0 &&
+                                (nx as usize) < width &&
+                                (ny as usize) < height &&
+                                previous[ny as usize][nx as usize]
+                        })
+                        .count();
+                    alive[y][x] = if previous[y][x] {
+                        neighbors >= rule.death_limit
+                    } else {
+                        neighbors > rule.birth_limit
+                    };
+                }
+            }
+        }
+        alive
+    }
+
+    fn has_enough_wood(&self, game_map: &GameMap) -> bool {
+        let wood_cells = game_map
+            .map
+            .iter()
+            .flatten()
+            .filter(|cell| {
+                cell.resource
+                    .as_ref()
+                    .is_some_and(|resource| resource.resource_type == ResourceType::Wood)
+            })
+            .count();
+        wood_cells as f32 >= (self.size * self.size) as f32 * MIN_WOOD_SHARE
+    }
+
+    fn find_spawn(
+        &self, random: &mut SeededRandom, symmetry: MapSymmetry, game_map: &GameMap,
+    ) -> Option<Position> {
+        let (half_width, half_height) = self.half_dimensions(symmetry);
+        let mut candidates = vec![];
+        for y in 1..half_height - 1 {
+            for x in 1..half_width - 1 {
+                let position = Position::new(x, y);
+                if game_map[position].resource.is_some() ||
+                    symmetry.mirror(position, self.size).distance_to(&position) < 4.0
+                {
+                    continue;
+                }
+                let next_to_wood = game_map.neighbors(position).any(|neighbor| {
+                    game_map[neighbor]
+                        .resource
+                        .as_ref()
+                        .is_some_and(|resource| resource.resource_type == ResourceType::Wood)
+                });
+                if next_to_wood {
+                    candidates.push(position);
+                }
+            }
+        }
+        if candidates.is_empty() {
+            return None;
+        }
+        let index = random.range(0, candidates.len() as i32) as usize;
+        Some(candidates[index])
+    }
+
+    fn spawn_players(
+        &self, symmetry: MapSymmetry, spawn: Position, game_map: &mut GameMap,
+    ) -> Vec<Player> {
+        let positions = [spawn, symmetry.mirror(spawn, self.size)];
+        (0..TEAM_COUNT)
+            .zip(positions)
+            .map(|(team, position)| {
+                let id = team as u32 + 1;
+                let city_id = format!("{}{}", CITY_ID_PREFIX, id);
+                let light_upkeep = GAME_CONSTANTS.parameters.light_upkeep[&ObjectType::City];
+                let mut city = City::new(team, city_id.clone(), 0.0, light_upkeep);
+                city.add_city_tile(game_map, position, 0.0);
+                game_map[position].road = GAME_CONSTANTS.parameters.max_road;
+
+                let mut player = Player::new(team);
+                player.cities.insert(city_id, city);
+                player.city_tile_count = 1;
+                player.units.push(Unit::new(
+                    team,
+                    UnitType::Worker,
+                    format!("{}{}", UNIT_ID_PREFIX, id),
+                    position,
+                    0.0,
+                ));
+                player
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resources(game_map: &GameMap) -> Vec<(Position, Resource)> {
+        game_map
+            .map
+            .iter()
+            .flatten()
+            .filter_map(|cell| cell.resource.clone().map(|resource| (cell.pos, resource)))
+            .collect()
+    }
+
+    #[test]
+    fn same_seed_generates_same_map() {
+        for size in MAP_SIZES {
+            let first = MapGenerator::new(7, size).unwrap().generate();
+            let second = MapGenerator::new(7, size).unwrap().generate();
+
+            assert_eq!(resources(&first.game_map), resources(&second.game_map));
+            for (first, second) in first.players.iter().zip(second.players.iter()) {
+                assert_eq!(first.units[0].pos, second.units[0].pos);
+            }
+        }
+
+        let other = MapGenerator::new(8, 16).unwrap().generate();
+        let first = MapGenerator::new(7, 16).unwrap().generate();
+        assert_ne!(resources(&first.game_map), resources(&other.game_map));
+    }
+
+    #[test]
+    fn generated_map_is_symmetric() {
+        for seed in 0..20 {
+            let simulator = MapGenerator::new(seed, 12).unwrap().generate();
+            let game_map = &simulator.game_map;
+            let symmetry = [MapSymmetry::Horizontal, MapSymmetry::Vertical]
+                .into_iter()
+                .find(|symmetry| {
+                    resources(game_map).into_iter().all(|(position, resource)| {
+                        game_map[symmetry.mirror(position, 12)].resource == Some(resource.clone())
+                    })
+                })
+                .unwrap();
+
+            let spawns: Vec<_> = simulator
+                .players
+                .iter()
+                .map(|player| player.units[0].pos)
+                .collect();
+            assert_eq!(symmetry.mirror(spawns[0], 12), spawns[1]);
+            for (team, player) in simulator.players.iter().enumerate() {
+                assert_eq!(player.team, team as TeamId);
+                assert_eq!(player.units.len(), 1);
+                assert_eq!(player.cities.len(), 1);
+                assert!(game_map[player.units[0].pos].citytile.is_some());
+            }
+        }
+    }
+
+    #[test]
+    fn generated_map_has_all_resource_types() {
+        let simulator = MapGenerator::new(3, 32).unwrap().generate();
+        for resource_type in [
+            ResourceType::Wood,
+            ResourceType::Coal,
+            ResourceType::Uranium,
+        ] {
+            assert!(resources(&simulator.game_map)
+                .iter()
+                .any(|(_, resource)| resource.resource_type == resource_type));
+        }
+    }
+
+    #[test]
+    fn unsupported_size_is_rejected() {
+        assert!(matches!(
+            MapGenerator::new(1, 20),
+            Err(LuxAiError::UnsupportedMapSize(20))
+        ));
+    }
+}
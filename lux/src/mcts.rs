@@ -0,0 +1,631 @@
+use std::fmt;
+
+use crate::*;
+
+/// Plan of one [`Unit`] lasting several turns, expanded into one action per
+/// turn
+#[derive(Eq, PartialEq, Clone, Copy, fmt::Debug, Hash)]
+pub enum MacroAction {
+    /// Stay in place
+    Stay,
+    /// Move to resource cell and collect resources around it
+    Gather(Position),
+    /// Move to own city tile, cargo is deposited on arrival
+    ReturnToCity(Position),
+    /// Move to empty cell and build city tile there once cargo is enough
+    Build(Position),
+}
+
+impl MacroAction {
+    /// Returns action of `unit` for current turn, moves follow path found by
+    /// [`PathFinder`]
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    /// - `unit` - [`Unit`] following this plan
+    /// - `game_map` - [`GameMap`] of current turn
+    /// - `players` - all [`players`][Player] of current turn
+    ///
+    /// # Returns
+    ///
+    /// [`Action`] or `None` if unit should not act this turn or target is
+    /// unreachable
+    pub fn action(&self, unit: &Unit, game_map: &GameMap, players: &[Player]) -> Option<Action> {
+        if !unit.can_act() {
+            return None;
+        }
+        match self {
+            Self::Stay => None,
+            Self::Build(target) if unit.pos == *target =>
+                unit.can_build(game_map).then(|| unit.build_city()),
+            Self::Gather(target) | Self::ReturnToCity(target) | Self::Build(target) => {
+                let direction = PathFinder::new(game_map, players, unit.team, unit.unit_type)
+                    .find_path(unit.pos, *target)?
+                    .first_direction();
+                (direction != Direction::Center).then(|| unit.move_(direction))
+            },
+        }
+    }
+}
+
+/// Policy choosing actions of units and city tiles during rollouts
+///
+/// # Examples
+///
+/// ```
+/// # use lux_ai_api::*;
+/// struct Idle;
+///
+/// impl RolloutPolicy for Idle {
+///     fn actions(&mut self, _: &Simulator, _: TeamId, _: &mut SeededRandom) -> Vec<Action> {
+///         vec![]
+///     }
+/// }
+/// ```
+pub trait RolloutPolicy {
+    /// Returns actions of all units and city tiles of `team`
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - mutable Self reference
+    /// - `simulator` - [`Simulator`] with state of current rollout turn
+    /// - `team` - team id to choose actions for
+    /// - `random` - [`SeededRandom`] of planner
+    ///
+    /// # Returns
+    ///
+    /// List of [`Action`]
+    fn actions(
+        &mut self, simulator: &Simulator, team: TeamId, random: &mut SeededRandom,
+    ) -> Vec<Action>;
+}
+
+/// Rollout policy of workers collecting nearest resource and returning to
+/// nearest city tile with full cargo, city tiles build workers while allowed
+/// and research otherwise
+#[derive(Clone, Copy, Default, fmt::Debug)]
+pub struct GreedyRollout;
+
+impl RolloutPolicy for GreedyRollout {
+    fn actions(
+        &mut self, simulator: &Simulator, team: TeamId, _random: &mut SeededRandom,
+    ) -> Vec<Action> {
+        let player = &simulator.players[team as usize];
+        let game_map = &simulator.game_map;
+        let mut actions = city_tile_actions(player, game_map, |_| true);
+
+        let resources: Vec<Position> = game_map
+            .map
+            .iter()
+            .flatten()
+            .filter(|cell| {
+                cell.has_resource() &&
+                    cell.resource
+                        .as_ref()
+                        .is_some_and(|resource| player.is_researched(resource.resource_type))
+            })
+            .map(|cell| cell.pos)
+            .collect();
+        let city_tiles: Vec<Position> = player
+            .cities
+            .values()
+            .flat_map(|city| city.city_tiles(game_map))
+            .map(|city_tile| city_tile.pos)
+            .collect();
+
+        for unit in player.units.iter() {
+            if !unit.can_act() || unit.unit_type != UnitType::Worker {
+                continue;
+            }
+            let target = if unit.get_cargo_space_left() > 0 {
+                nearest_positions(unit.pos, resources.iter().copied(), 1)
+            } else if unit.can_build(game_map) {
+                actions.push(unit.build_city());
+                continue;
+            } else {
+                nearest_positions(unit.pos, city_tiles.iter().copied(), 1)
+            };
+            if let Some(target) = target.first() {
+                let direction = unit.pos.direction_to(target);
+                if direction != Direction::Center {
+                    actions.push(unit.move_(direction));
+                }
+            }
+        }
+        actions
+    }
+}
+
+/// Rollout policy of units moving in random directions and workers building
+/// city tiles at random, city tiles build workers or research at random
+#[derive(Clone, Copy, Default, fmt::Debug)]
+pub struct RandomRollout;
+
+impl RolloutPolicy for RandomRollout {
+    fn actions(
+        &mut self, simulator: &Simulator, team: TeamId, random: &mut SeededRandom,
+    ) -> Vec<Action> {
+        let player = &simulator.players[team as usize];
+        let game_map = &simulator.game_map;
+        let mut actions = city_tile_actions(player, game_map, |_| random.next_f32() < 0.5);
+
+        for unit in player.units.iter().filter(|unit| unit.can_act()) {
+            if unit.can_build(game_map) && random.next_f32() < 0.2 {
+                actions.push(unit.build_city());
+                continue;
+            }
+            let directions = Direction::DIRECTIONS;
+            let index = random.range(0, directions.len() as i32 + 1) as usize;
+            if let Some(direction) = directions.get(index) {
+                actions.push(unit.move_(*direction));
+            }
+        }
+        actions
+    }
+}
+
+/// Parameters of [`MctsPlanner`]
+#[derive(Clone, fmt::Debug)]
+pub struct MctsConfig {
+    /// Turns simulated by each rollout
+    pub horizon: TurnAmount,
+
+    /// Exploration constant of UCB1
+    pub exploration: f32,
+
+    /// Max rollouts per turn, search stops earlier if time budget expires
+    pub max_iterations: usize,
+
+    /// Max targets of each kind of [`MacroAction`] considered per unit
+    pub targets_per_kind: usize,
+
+    /// Seed of rollouts random numbers
+    pub seed: u64,
+}
+
+impl Default for MctsConfig {
+    fn default() -> Self {
+        Self {
+            horizon:          10,
+            exploration:      1.4,
+            max_iterations:   1000,
+            targets_per_kind: 2,
+            seed:             0,
+        }
+    }
+}
+
+/// Result of [`MctsPlanner::plan`]
+#[derive(Clone, Default, PartialEq, fmt::Debug)]
+pub struct MctsPlan {
+    /// Chosen [`MacroAction`] of each unit by unit id
+    pub macros: Vec<(EntityId, MacroAction)>,
+
+    /// Actions of units and city tiles for current turn, moves do not collide
+    pub actions: Vec<Action>,
+
+    /// Count of rollouts performed
+    pub iterations: usize,
+}
+
+/// Node of search tree, children are stored contiguously in nodes arena
+#[derive(Clone, Default)]
+struct Node {
+    visits:       u32,
+    total_reward: f32,
+    children:     Option<(usize, usize)>,
+}
+
+/// Monte Carlo Tree Search over [`MacroActions`][MacroAction] of own units
+///
+/// Depth `d` of the tree chooses macro-action of `d`-th unit, so every path
+/// from root to leaf is a joint plan of all units. Each iteration selects
+/// plan with UCB1, plays it for [`MctsConfig::horizon`] turns on
+/// [`Simulator`], while [`RolloutPolicy`] controls enemy, city tiles and units
+/// built during rollout, and backpropagates evaluation of final state
+///
+/// Rollouts clone [`Simulator`] rather than [`CompactState`], which has no
+/// turn resolution of its own
+///
+/// # Examples
+///
+/// ```no_run
+/// # use lux_ai_api::*;
+/// # let mut environment = Environment::new();
+/// let mut planner = MctsPlanner::new(MctsConfig::default(), GreedyRollout);
+/// environment.run_with_budget(TimeBudget::default(), &mut |agent, environment, budget| {
+///     for action in planner.plan(agent, budget).actions {
+///         environment.write_action(action);
+///     }
+///     Ok(())
+/// })?;
+/// # Ok::<(), LuxAiError>(())
+/// ```
+///
+/// # See also
+///
+/// Check <https://www.lux-ai.org/specs-2021>
+pub struct MctsPlanner<P: RolloutPolicy> {
+    /// Search parameters
+    pub config: MctsConfig,
+
+    /// Policy of rollouts
+    pub policy: P,
+
+    random: SeededRandom,
+}
+
+impl<P: RolloutPolicy> MctsPlanner<P> {
+    /// Creates [`MctsPlanner`]
+    ///
+    /// # Parameters
+    ///
+    /// - `config` - [`MctsConfig`] with search parameters
+    /// - `policy` - [`RolloutPolicy`] of rollouts
+    ///
+    /// # Returns
+    ///
+    /// A new created [`MctsPlanner`]
+    pub fn new(config: MctsConfig, policy: P) -> Self {
+        let random = SeededRandom::new(config.seed);
+        Self {
+            config,
+            policy,
+            random,
+        }
+    }
+
+    /// Returns macro-actions considered for `unit`: gathering from nearest
+    /// clusters, building next to them, returning to nearest city tiles and
+    /// staying
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    /// - `agent` - [`Agent`] reference
+    /// - `clusters` - [`ResourceClusters`][ResourceCluster] of current turn
+    /// - `unit` - own [`Unit`]
+    ///
+    /// # Returns
+    ///
+    /// List of [`MacroAction`], most promising first
+    pub fn candidates(
+        &self, agent: &Agent, clusters: &[ResourceCluster], unit: &Unit,
+    ) -> Vec<MacroAction> {
+        let limit = self.config.targets_per_kind;
+        let player = agent.player();
+        let mut candidates = vec![];
+        if unit.unit_type == UnitType::Worker {
+            let researched = clusters
+                .iter()
+                .filter(|cluster| player.is_researched(cluster.resource_type));
+            let gather = researched
+                .clone()
+                .flat_map(|cluster| nearest_positions(unit.pos, cluster.cells.iter().copied(), 1));
+            let build = researched.flat_map(|cluster| cluster.perimeter.iter().copied());
+            candidates.extend(
+                nearest_positions(unit.pos, gather, limit)
+                    .into_iter()
+                    .map(MacroAction::Gather),
+            );
+            candidates.extend(
+                nearest_positions(unit.pos, build, limit)
+                    .into_iter()
+                    .map(MacroAction::Build),
+            );
+        }
+        let city_tiles = player
+            .cities
+            .values()
+            .flat_map(|city| city.city_tiles(&agent.game_map))
+            .map(|city_tile| city_tile.pos);
+        candidates.extend(
+            nearest_positions(unit.pos, city_tiles, limit)
+                .into_iter()
+                .map(MacroAction::ReturnToCity),
+        );
+        candidates.push(MacroAction::Stay);
+        candidates
+    }
+
+    /// Searches best macro-actions of own units until time budget expires or
+    /// [`MctsConfig::max_iterations`] rollouts are done
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - mutable Self reference
+    /// - `agent` - [`Agent`] with state of current turn
+    /// - `budget` - [`TimeBudget`] of current turn
+    ///
+    /// # Returns
+    ///
+    /// [`MctsPlan`] with chosen macro-actions and actions of current turn
+    pub fn plan(&mut self, agent: &Agent, budget: &TimeBudget) -> MctsPlan {
+        let clusters = ResourceCluster::find_all(&agent.game_map, &agent.players);
+        let units = &agent.player().units;
+        let candidates: Vec<Vec<MacroAction>> = units
+            .iter()
+            .map(|unit| self.candidates(agent, &clusters, unit))
+            .collect();
+        let root = Simulator::from_agent(agent);
+
+        let mut nodes = vec![Node::default()];
+        let mut iterations = 0;
+        while iterations < self.config.max_iterations && !budget.is_expired() {
+            let path = self.select(&mut nodes, &candidates);
+            let macros: Vec<(EntityId, MacroAction)> = path
+                .iter()
+                .enumerate()
+                .map(|(depth, (_, choice))| (units[depth].id.clone(), candidates[depth][*choice]))
+                .collect();
+            let reward = self.rollout(&root, agent.team, &macros);
+            for index in std::iter::once(0).chain(path.iter().map(|(node, _)| *node)) {
+                nodes[index].visits += 1;
+                nodes[index].total_reward += reward;
+            }
+            iterations += 1;
+        }
+
+        let mut plan = MctsPlan {
+            iterations,
+            ..MctsPlan::default()
+        };
+        let mut node = 0;
+        for (unit, candidates) in units.iter().zip(candidates.iter()) {
+            let choice = match nodes[node].children {
+                Some((first, count)) => {
+                    let best = (first..first + count)
+                        .max_by_key(|child| nodes[*child].visits)
+                        .unwrap();
+                    node = best;
+                    best - first
+                },
+                None => 0,
+            };
+            plan.macros.push((unit.id.clone(), candidates[choice]));
+        }
+
+        let mut coordinator = MoveCoordinator::from_agent(agent);
+        for ((_, macro_action), unit) in plan.macros.iter().zip(units.iter()) {
+            match macro_action.action(unit, &agent.game_map, &agent.players) {
+                Some(ActionKind::Move { direction, .. }) => coordinator.request(unit, direction),
+                Some(action) => plan.actions.push(action),
+                None => {},
+            }
+        }
+        plan.actions.extend(coordinator.resolve().actions);
+        let city_tile_actions = self
+            .policy
+            .actions(&root, agent.team, &mut self.random)
+            .into_iter()
+            .filter(|action| action.city_tile_position().is_some());
+        plan.actions.extend(city_tile_actions);
+        plan
+    }
+
+    fn select(
+        &mut self, nodes: &mut Vec<Node>, candidates: &[Vec<MacroAction>],
+    ) -> Vec<(usize, usize)> {
+        let mut path = vec![];
+        let mut node = 0;
+        for candidates in candidates.iter() {
+            let (first, count) = match nodes[node].children {
+                Some(children) => children,
+                None => {
+                    let children = (nodes.len(), candidates.len());
+                    nodes.resize(nodes.len() + candidates.len(), Node::default());
+                    nodes[node].children = Some(children);
+                    children
+                },
+            };
+            let parent_visits = nodes[node].visits.max(1) as f32;
+            let child = (first..first + count)
+                .max_by(|a, b| {
+                    let a = self.ucb(&nodes[*a], parent_visits);
+                    let b = self.ucb(&nodes[*b], parent_visits);
+                    a.total_cmp(&b)
+                })
+                .unwrap();
+            path.push((child, child - first));
+            node = child;
+        }
+        path
+    }
+
+    fn ucb(&self, node: &Node, parent_visits: f32) -> f32 {
+        if node.visits == 0 {
+            return f32::INFINITY;
+        }
+        let visits = node.visits as f32;
+        node.total_reward / visits + self.config.exploration * (parent_visits.ln() / visits).sqrt()
+    }
+
+    fn rollout(
+        &mut self, root: &Simulator, team: TeamId, macros: &[(EntityId, MacroAction)],
+    ) -> f32 {
+        let mut simulator = root.clone();
+        for _ in 0..self.config.horizon {
+            if simulator.is_game_over() {
+                break;
+            }
+            let mut actions = vec![];
+            for current in 0..TEAM_COUNT {
+                let mut team_actions = self.policy.actions(&simulator, current, &mut self.random);
+                if current == team {
+                    team_actions.retain(|action| {
+                        action
+                            .unit_id()
+                            .is_none_or(|unit_id| macros.iter().all(|(id, _)| id != unit_id))
+                    });
+                    let units = &simulator.players[team as usize].units;
+                    for (unit_id, macro_action) in macros.iter() {
+                        let action =
+                            units
+                                .iter()
+                                .find(|unit| &unit.id == unit_id)
+                                .and_then(|unit| {
+                                    macro_action.action(
+                                        unit,
+                                        &simulator.game_map,
+                                        &simulator.players,
+                                    )
+                                });
+                        team_actions.extend(action);
+                    }
+                }
+                actions.push(team_actions);
+            }
+            simulator.step(&actions);
+        }
+        Self::evaluate(&simulator, team)
+    }
+
+    fn evaluate(simulator: &Simulator, team: TeamId) -> f32 {
+        let score = |player: &Player| {
+            let cargo: ResourceAmount = player.units.iter().map(Unit::cargo_space_used).sum();
+            player.city_tile_count as f32 * 10.0 + player.units.len() as f32 + cargo as f32 / 100.0
+        };
+        let own = score(&simulator.players[team as usize]);
+        let enemy = score(&simulator.players[(1 - team) as usize]);
+        0.5 + 0.5 * ((own - enemy) / 10.0).tanh()
+    }
+}
+
+/// Returns actions of city tiles of `player`: building workers while unit
+/// limit allows and `build` agrees, researching otherwise
+fn city_tile_actions(
+    player: &Player, game_map: &GameMap, mut build: impl FnMut(&CityTile) -> bool,
+) -> Vec<Action> {
+    let mut actions = vec![];
+    let mut units = player.units.len();
+    let research_done = player.is_researched(ResourceType::Uranium);
+    for city_tile in player
+        .cities
+        .values()
+        .flat_map(|city| city.city_tiles(game_map))
+        .filter(|city_tile| city_tile.can_act())
+    {
+        if units < player.city_tile_count as usize && build(city_tile) {
+            units += 1;
+            actions.push(city_tile.build_worker());
+        } else if !research_done {
+            actions.push(city_tile.research());
+        }
+    }
+    actions
+}
+
+/// Returns up to `limit` distinct positions nearest to `from`
+fn nearest_positions(
+    from: Position, positions: impl Iterator<Item = Position>, limit: usize,
+) -> Vec<Position> {
+    let mut positions: Vec<Position> = positions.collect();
+    positions.sort_by_key(|position| {
+        (
+            from.distance_to(position) as Coordinate,
+            position.y,
+            position.x,
+        )
+    });
+    positions.dedup();
+    positions.truncate(limit);
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    const MAX_ITERATIONS: usize = 50;
+    const TURNS: TurnAmount = 30;
+
+    fn planner() -> MctsPlanner<GreedyRollout> {
+        let config = MctsConfig {
+            max_iterations: MAX_ITERATIONS,
+            seed: 42,
+            ..MctsConfig::default()
+        };
+        MctsPlanner::new(config, GreedyRollout)
+    }
+
+    fn budget() -> TimeBudget {
+        TimeBudget::new(Duration::from_secs(60), Duration::ZERO, Duration::ZERO)
+    }
+
+    /// Generated match played by planner against greedy rollout for a few
+    /// turns, so team has several units
+    fn agent() -> Agent {
+        let mut simulator = MapGenerator::new(7, 12).unwrap().generate();
+        let mut planner = planner();
+        let mut random = SeededRandom::new(7);
+        for _ in 0..TURNS {
+            let actions = vec![
+                planner.plan(&simulator.to_agent(0), &budget()).actions,
+                GreedyRollout.actions(&simulator, 1, &mut random),
+            ];
+            simulator.step(&actions);
+        }
+        simulator.to_agent(0)
+    }
+
+    fn assert_legal(agent: &Agent, actions: &[Action]) {
+        let mut validator = ActionValidator::from_agent(agent);
+        for action in actions.iter() {
+            assert_eq!(validator.validate(action), Ok(()), "{}", action);
+        }
+    }
+
+    #[test]
+    fn macro_action_moves_around_enemy_city_tile() {
+        let mut game_map = GameMap::new(5, 5);
+        let city_tile = CityTile::new(1, "c_1".to_string(), Position::new(1, 2), 0.0);
+        game_map.add_city_tile(city_tile);
+        let players = vec![Player::new(0), Player::new(1)];
+        let unit = Unit::new(
+            0,
+            UnitType::Worker,
+            "u_1".to_string(),
+            Position::new(0, 2),
+            0.0,
+        );
+
+        let action = MacroAction::Gather(Position::new(4, 2)).action(&unit, &game_map, &players);
+
+        assert!(matches!(
+            action,
+            Some(ActionKind::Move {
+                direction: Direction::North | Direction::South,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn plan_is_legal_and_deterministic() {
+        let agent = agent();
+        assert!(agent.player().units.len() > 1);
+
+        let plan = planner().plan(&agent, &budget());
+
+        assert_eq!(plan.iterations, MAX_ITERATIONS);
+        assert_eq!(plan.macros.len(), agent.player().units.len());
+        assert!(!plan.actions.is_empty());
+        assert_legal(&agent, &plan.actions);
+        assert_eq!(planner().plan(&agent, &budget()), plan);
+    }
+
+    #[test]
+    fn plan_stops_when_budget_expires() {
+        let agent = agent();
+        let budget = TimeBudget::new(Duration::ZERO, Duration::ZERO, Duration::ZERO);
+
+        let plan = planner().plan(&agent, &budget);
+
+        assert_eq!(plan.iterations, 0);
+        assert_eq!(plan.macros.len(), agent.player().units.len());
+        assert_legal(&agent, &plan.actions);
+    }
+}
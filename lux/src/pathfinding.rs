@@ -0,0 +1,385 @@
+use std::{cmp,
+          collections::{BinaryHeap, HashMap, HashSet},
+          fmt};
+
+use crate::*;
+
+/// Path found by [`PathFinder`]
+#[derive(Clone, PartialEq, fmt::Debug)]
+pub struct Path {
+    /// Positions along the path, starting with start position and ending with
+    /// goal position
+    pub positions: Vec<Position>,
+
+    /// Total cost of the path in turns of cooldown
+    pub cost: Cooldown,
+}
+
+impl Path {
+    /// Returns [`Direction`] of first step along the path
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// [`Direction`] to take, [`Direction::Center`] if path is already at goal
+    pub fn first_direction(&self) -> Direction {
+        match self.positions.as_slice() {
+            [start, next, ..] => start.direction_to(next),
+            _ => Direction::Center,
+        }
+    }
+
+    /// Returns count of moves along the path
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// Count of moves
+    pub fn len(&self) -> usize { self.positions.len().saturating_sub(1) }
+
+    /// Whether or not path has no moves, i.e. start is goal
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// `bool` value
+    pub fn is_empty(&self) -> bool { self.len() == 0 }
+}
+
+/// Open set entry of A* search, ordered by lowest estimated cost first
+#[derive(PartialEq, fmt::Debug)]
+struct Node {
+    estimate: Cooldown,
+    position: Position,
+}
+
+impl Eq for Node {}
+
+impl Ord for Node {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        other
+            .estimate
+            .partial_cmp(&self.estimate)
+            .unwrap_or(cmp::Ordering::Equal)
+    }
+}
+
+impl PartialOrd for Node {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> { Some(self.cmp(other)) }
+}
+
+/// Road and cooldown aware A* pathfinding over [`GameMap`] cells
+///
+/// Enemy [`CityTiles`][CityTile] and cells occupied by units are blocked,
+/// except units standing on friendly city tiles, where units can stack. Cost of
+/// moving into a cell is cooldown unit gains after the move:
+/// `unit_action_cooldown` reduced by [`Cell::road`], but at least one turn
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// # use lux_ai_api::*;
+/// # let players = vec![Player::new(0), Player::new(1)];
+/// # let mut simulator = Simulator::new(1, GameMap::new(12, 12), players);
+/// # let (pos, id) = (Position::new(1, 1), "u_1".to_string());
+/// # simulator.players[0].units.push(Unit::new(0, UnitType::Worker, id, pos, 0.0));
+/// # let agent = simulator.to_agent(0);
+/// # let mut environment = Environment::with_io(io::empty(), Vec::new());
+/// # let unit = &agent.player().units[0];
+/// # let target = Position::new(0, 0);
+/// let path_finder = PathFinder::new(&agent.game_map, &agent.players, unit.team, unit.unit_type);
+/// if let Some(path) = path_finder.find_path(unit.pos, target) {
+///     environment.write_action(unit.move_(path.first_direction()));
+/// }
+/// # Ok::<(), LuxAiError>(())
+/// ```
+///
+/// # See also
+///
+/// Check <https://www.lux-ai.org/specs-2021#Cooldown>
+/// Check <https://www.lux-ai.org/specs-2021#Roads>
+pub struct PathFinder<'a> {
+    game_map:      &'a GameMap,
+    team:          TeamId,
+    base_cooldown: Cooldown,
+    blocked:       HashSet<Position>,
+}
+
+impl<'a> PathFinder<'a> {
+    /// Creates [`PathFinder`] for unit of given team and type
+    ///
+    /// # Parameters
+    ///
+    /// - `game_map` - [`GameMap`] to plan over
+    /// - `players` - all [`players`][Player], whose units occupy cells
+    /// - `team` - team id of moving unit
+    /// - `unit_type` - [`UnitType`] of moving unit
+    ///
+    /// # Returns
+    ///
+    /// A new created [`PathFinder`]
+    pub fn new(
+        game_map: &'a GameMap, players: &[Player], team: TeamId, unit_type: UnitType,
+    ) -> Self {
+        let base_cooldown = GAME_CONSTANTS.parameters.unit_action_cooldown[&unit_type] as Cooldown;
+        let mut path_finder = Self {
+            game_map,
+            team,
+            base_cooldown,
+            blocked: HashSet::new(),
+        };
+        for unit in players.iter().flat_map(|player| player.units.iter()) {
+            if !path_finder.is_friendly_city_tile(unit.pos) {
+                path_finder.block(unit.pos);
+            }
+        }
+        path_finder
+    }
+
+    /// Marks `position` as blocked, e.g. cell reserved by other unit's move
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - mutable Self reference
+    /// - `position` - [`Position`] to block
+    ///
+    /// # Returns
+    ///
+    /// Nothing
+    pub fn block(&mut self, position: Position) { self.blocked.insert(position); }
+
+    /// Whether or not unit can move into `position`
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    /// - `position` - [`Position`] to check
+    ///
+    /// # Returns
+    ///
+    /// `bool` value
+    pub fn is_passable(&self, position: Position) -> bool {
+        if !self.game_map.contains(position) || self.blocked.contains(&position) {
+            return false;
+        }
+        self.game_map
+            .city_tile_at(position)
+            .is_none_or(|city_tile| city_tile.teamid == self.team)
+    }
+
+    /// Returns cost of moving into `position`, in turns of cooldown
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    /// - `position` - [`Position`] to move into
+    ///
+    /// # Returns
+    ///
+    /// Cooldown gained by the move
+    pub fn move_cost(&self, position: Position) -> Cooldown {
+        (self.base_cooldown - self.game_map[position].road).max(1.0)
+    }
+
+    /// Finds cheapest path from `start` to `goal`
+    ///
+    /// `goal` is reachable even if it is occupied by unit, but not if it is
+    /// enemy city tile
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    /// - `start` - [`Position`] to start from
+    /// - `goal` - [`Position`] to reach
+    ///
+    /// # Returns
+    ///
+    /// Found [`Path`] or `None` if `goal` is unreachable
+    pub fn find_path(&self, start: Position, goal: Position) -> Option<Path> {
+        if !self.game_map.contains(start) || !self.game_map.contains(goal) {
+            return None;
+        }
+
+        let mut open = BinaryHeap::new();
+        let mut costs: HashMap<Position, Cooldown> = HashMap::new();
+        let mut came_from: HashMap<Position, Position> = HashMap::new();
+        open.push(Node {
+            estimate: goal.distance_to(&start),
+            position: start,
+        });
+        costs.insert(start, 0.0);
+
+        while let Some(Node { estimate, position }) = open.pop() {
+            let cost = costs[&position];
+            if position == goal {
+                return Some(Self::reconstruct_path(&came_from, goal, cost));
+            }
+            if estimate > cost + goal.distance_to(&position) {
+                continue;
+            }
+
+            for next in self.game_map.neighbors(position) {
+                let passable =
+                    self.is_passable(next) || (next == goal && !self.is_enemy_city_tile(next));
+                if !passable {
+                    continue;
+                }
+                let next_cost = cost + self.move_cost(next);
+                if costs.get(&next).is_none_or(|known| next_cost < *known) {
+                    costs.insert(next, next_cost);
+                    came_from.insert(next, position);
+                    open.push(Node {
+                        estimate: next_cost + goal.distance_to(&next),
+                        position: next,
+                    });
+                }
+            }
+        }
+
+        None
+    }
+
+    fn reconstruct_path(
+        came_from: &HashMap<Position, Position>, goal: Position, cost: Cooldown,
+    ) -> Path {
+        let mut positions = vec![goal];
+        let mut position = goal;
+        while let Some(previous) = came_from.get(&position) {
+            positions.push(*previous);
+            position = *previous;
+        }
+        positions.reverse();
+        Path { positions, cost }
+    }
+
+    fn is_friendly_city_tile(&self, position: Position) -> bool {
+        self.game_map
+            .city_tile_at(position)
+            .is_some_and(|city_tile| city_tile.teamid == self.team)
+    }
+
+    fn is_enemy_city_tile(&self, position: Position) -> bool {
+        self.game_map
+            .city_tile_at(position)
+            .is_some_and(|city_tile| city_tile.teamid != self.team)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add_city_tile(game_map: &mut GameMap, team: TeamId, x: i32, y: i32) {
+        let city_tile = CityTile::new(team, format!("c_{}", team + 1), Position::new(x, y), 0.0);
+        game_map.add_city_tile(city_tile);
+    }
+
+    #[test]
+    fn path_avoids_enemy_city_tiles() {
+        let mut game_map = GameMap::new(5, 5);
+        for y in 0..3 {
+            add_city_tile(&mut game_map, 1, 2, y);
+        }
+        let players = vec![Player::new(0), Player::new(1)];
+        let path_finder = PathFinder::new(&game_map, &players, 0, UnitType::Worker);
+
+        let path = path_finder
+            .find_path(Position::new(0, 1), Position::new(4, 1))
+            .unwrap();
+
+        assert_eq!(path.len(), 8);
+        assert!(path
+            .positions
+            .iter()
+            .all(|position| position.x != 2 || position.y == 3));
+    }
+
+    #[test]
+    fn path_passes_friendly_city_tiles() {
+        let mut game_map = GameMap::new(5, 5);
+        add_city_tile(&mut game_map, 0, 2, 1);
+        let players = vec![Player::new(0), Player::new(1)];
+        let path_finder = PathFinder::new(&game_map, &players, 0, UnitType::Worker);
+
+        let path = path_finder
+            .find_path(Position::new(0, 1), Position::new(4, 1))
+            .unwrap();
+
+        assert_eq!(path.len(), 4);
+        assert!(path.positions.contains(&Position::new(2, 1)));
+    }
+
+    #[test]
+    fn path_prefers_roads() {
+        let mut game_map = GameMap::new(5, 5);
+        for x in 0..5 {
+            game_map[Position::new(x, 1)].road = GAME_CONSTANTS.parameters.max_road;
+        }
+        let players = vec![Player::new(0), Player::new(1)];
+        let path_finder = PathFinder::new(&game_map, &players, 0, UnitType::Cart);
+
+        let path = path_finder
+            .find_path(Position::new(0, 0), Position::new(4, 0))
+            .unwrap();
+
+        assert_eq!(path.len(), 6);
+        assert_eq!(path.cost, 8.0);
+        assert!(path.positions.contains(&Position::new(2, 1)));
+    }
+
+    #[test]
+    fn unreachable_goal_has_no_path() {
+        let mut game_map = GameMap::new(5, 5);
+        add_city_tile(&mut game_map, 1, 4, 4);
+        for (x, y) in [(1, 0), (1, 1), (0, 1)] {
+            add_city_tile(&mut game_map, 1, x, y);
+        }
+        let players = vec![Player::new(0), Player::new(1)];
+        let path_finder = PathFinder::new(&game_map, &players, 0, UnitType::Worker);
+
+        assert_eq!(
+            path_finder.find_path(Position::new(2, 2), Position::new(4, 4)),
+            None
+        );
+        assert_eq!(
+            path_finder.find_path(Position::new(2, 2), Position::new(0, 0)),
+            None
+        );
+        assert_eq!(
+            path_finder.find_path(Position::new(2, 2), Position::new(5, 2)),
+            None
+        );
+    }
+
+    #[test]
+    fn units_block_path_but_not_goal() {
+        let game_map = GameMap::new(3, 1);
+        let mut players = vec![Player::new(0), Player::new(1)];
+        let (position, id) = (Position::new(1, 0), "u_2".to_string());
+        players[1]
+            .units
+            .push(Unit::new(1, UnitType::Worker, id, position, 0.0));
+        let path_finder = PathFinder::new(&game_map, &players, 0, UnitType::Worker);
+
+        assert_eq!(
+            path_finder.find_path(Position::new(0, 0), Position::new(2, 0)),
+            None
+        );
+        assert_eq!(
+            path_finder
+                .find_path(Position::new(0, 0), Position::new(1, 0))
+                .map(|path| path.first_direction()),
+            Some(Direction::East)
+        );
+    }
+}
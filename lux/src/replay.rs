@@ -0,0 +1,190 @@
+use std::{fmt, fs, io, path};
+
+use serde::Deserialize;
+
+use crate::*;
+
+/// Observation of one agent in Kaggle episode JSON
+#[derive(Deserialize, Default, fmt::Debug)]
+#[serde(default)]
+struct EpisodeObservation {
+    updates: Vec<String>,
+    width:   Option<Coordinate>,
+    height:  Option<Coordinate>,
+}
+
+/// State of one agent at one step of Kaggle episode JSON
+#[derive(Deserialize, fmt::Debug)]
+struct EpisodeAgentStep {
+    #[serde(default)]
+    action:      Option<Vec<String>>,
+    #[serde(default)]
+    observation: EpisodeObservation,
+}
+
+/// Kaggle episode JSON, only fields required to rebuild match
+#[derive(Deserialize, fmt::Debug)]
+struct Episode {
+    steps: Vec<Vec<EpisodeAgentStep>>,
+}
+
+/// State of match at one turn of [`Replay`]
+#[derive(Clone, fmt::Debug)]
+pub struct ReplayTurn {
+    /// [`Agent`] state as bot saw it at this turn
+    pub agent: Agent,
+
+    /// [`Actions`][Action] submitted by each team at this turn, indexed by
+    /// team id. Actions which can not be parsed are skipped, as referee does
+    pub actions: Vec<Vec<Action>>,
+}
+
+/// Match rebuilt from Kaggle episode JSON as sequence of [`Agent`] states
+///
+/// Every turn is read with the same [`InputMessage`] parsing that
+/// [`Agent::update_turn`] uses, so snapshots match what bot saw during match,
+/// including [`WorldModel`] changes between turns
+///
+/// # Examples
+///
+/// ```no_run
+/// # use lux_ai_api::*;
+/// let replay = Replay::from_file("episode.json", 0)?;
+/// for turn in replay.turns.iter() {
+///     eprintln!("{}: {:?}", turn.agent.turn, turn.actions[0]);
+/// }
+/// # Ok::<(), LuxAiError>(())
+/// ```
+#[derive(Clone, fmt::Debug)]
+pub struct Replay {
+    /// Team id from which point of view [`Agent`] states are built
+    pub team: TeamId,
+
+    /// States of match, one per turn
+    pub turns: Vec<ReplayTurn>,
+}
+
+impl Replay {
+    /// Loads [`Replay`] from Kaggle episode JSON file
+    ///
+    /// # Parameters
+    ///
+    /// - `path` - path to episode JSON file
+    /// - `team` - team id from which point of view to build [`Agent`] states
+    ///
+    /// # Returns
+    ///
+    /// Loaded [`Replay`] or error
+    pub fn from_file<P: AsRef<path::Path>>(path: P, team: TeamId) -> LuxAiResult<Self> {
+        let file = fs::File::open(path)?;
+        Self::from_reader(io::BufReader::new(file), team)
+    }
+
+    /// Loads [`Replay`] from Kaggle episode JSON
+    ///
+    /// # Parameters
+    ///
+    /// - `reader` - reader of episode JSON
+    /// - `team` - team id from which point of view to build [`Agent`] states
+    ///
+    /// # Returns
+    ///
+    /// Loaded [`Replay`] or error
+    pub fn from_reader<R: io::Read>(reader: R, team: TeamId) -> LuxAiResult<Self> {
+        let episode: Episode = serde_json::from_reader(reader)?;
+        let first_step = episode
+            .steps
+            .first()
+            .and_then(|step| step.first())
+            .ok_or_else(|| LuxAiError::ReplayFormat("episode has no steps".to_string()))?;
+
+        let (dimensions, header_length) = Self::read_dimensions(&first_step.observation)?;
+        let mut environment = Self::environment(&[team.to_string(), dimensions]);
+        let mut agent = Agent::new(&mut environment)?;
+
+        let mut turns = vec![];
+        for (index, step) in episode.steps.iter().enumerate() {
+            let updates = step
+                .first()
+                .map(|agent_step| agent_step.observation.updates.as_slice())
+                .unwrap_or_default();
+            let updates = if index == 0 {
+                &updates[header_length..]
+            } else {
+                updates
+            };
+            let mut lines = updates.to_vec();
+            if lines.last().map(|line| line.trim()) != Some(Commands::DONE) {
+                lines.push(Commands::DONE.to_string());
+            }
+            agent.update_turn(&mut Self::environment(&lines))?;
+
+            let actions = (0..TEAM_COUNT as usize)
+                .map(|team| {
+                    episode
+                        .steps
+                        .get(index + 1)
+                        .and_then(|next_step| next_step.get(team))
+                        .and_then(|agent_step| agent_step.action.as_ref())
+                        .map(|actions| Self::parse_actions(actions))
+                        .unwrap_or_default()
+                })
+                .collect();
+            turns.push(ReplayTurn {
+                agent: agent.clone(),
+                actions,
+            });
+        }
+
+        Ok(Self { team, turns })
+    }
+
+    /// Returns state of match at given turn
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    /// - `turn` - turn index, as in [`Agent::turn`]
+    ///
+    /// # Returns
+    ///
+    /// [`ReplayTurn`] reference or `None` if match has no such turn
+    pub fn turn(&self, turn: TurnAmount) -> Option<&ReplayTurn> {
+        self.turns
+            .iter()
+            .find(|replay_turn| replay_turn.agent.turn == turn)
+    }
+
+    /// Reads map dimensions from first observation, which starts with team id
+    /// and dimensions lines in Kaggle episodes
+    fn read_dimensions(observation: &EpisodeObservation) -> LuxAiResult<(String, usize)> {
+        let updates = &observation.updates;
+        let is_header = updates.len() >= 2 &&
+            updates[0].split_whitespace().count() == 1 &&
+            updates[1].split_whitespace().count() == 2;
+        if is_header {
+            return Ok((updates[1].clone(), 2));
+        }
+        match (observation.width, observation.height) {
+            (Some(width), Some(height)) => Ok((format!("{} {}", width, height), 0)),
+            _ => Err(LuxAiError::ReplayFormat(
+                "map dimensions not found".to_string(),
+            )),
+        }
+    }
+
+    fn environment(lines: &[String]) -> Environment<io::Cursor<Vec<u8>>, io::Sink> {
+        let input = lines
+            .iter()
+            .map(|line| format!("{}\n", line.trim_end()))
+            .collect::<String>();
+        Environment::with_io(io::Cursor::new(input.into_bytes()), io::sink())
+    }
+
+    fn parse_actions(actions: &[String]) -> Vec<Action> {
+        actions
+            .iter()
+            .filter_map(|action| action.parse().ok())
+            .collect()
+    }
+}
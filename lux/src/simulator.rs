@@ -0,0 +1,1165 @@
+use std::{collections::{HashMap, HashSet},
+          fmt};
+
+use crate::*;
+
+/// Prefix of [`Unit`] ids generated by referee
+pub(crate) const UNIT_ID_PREFIX: &str = "u_";
+
+/// Prefix of [`City`] ids generated by referee
+pub(crate) const CITY_ID_PREFIX: &str = "c_";
+
+/// Parses number of [`Unit`] or [`City`] id generated by referee
+pub(crate) fn parse_entity_id(id: &str, prefix: &str) -> Option<u32> {
+    id.strip_prefix(prefix)?.parse().ok()
+}
+
+/// Validated action of [`Unit`] ready to be resolved
+#[derive(Clone, fmt::Debug)]
+enum UnitOrder {
+    Move(Position),
+    Transfer(EntityId, ResourceType, ResourceAmount),
+    BuildCity,
+    Pillage,
+}
+
+/// Validated action of [`CityTile`] ready to be resolved
+#[derive(Clone, Copy, fmt::Debug)]
+enum CityTileOrder {
+    Research,
+    BuildUnit(UnitType),
+}
+
+/// [`UnitOrder`] of [`Unit`] with given team and index in [`Player::units`]
+type UnitOrders = Vec<(TeamId, usize, UnitOrder)>;
+
+/// [`CityTileOrder`] of [`CityTile`] with given team at given position
+type CityTileOrders = Vec<(TeamId, Position, CityTileOrder)>;
+
+/// Local turn resolution model of Lux AI 2021 rules
+///
+/// Takes [`GameMap`] and [`players`][Player] state, actions of both teams and
+/// resolves them into next turn state the same way referee does: movement,
+/// collection, city fuel burn at night, wood regrowth, cooldowns and research.
+/// All mechanics are driven by `GAME_CONSTANTS.parameters`
+///
+/// # Examples
+///
+/// ```
+/// # use lux_ai_api::*;
+/// # let players = vec![Player::new(0), Player::new(1)];
+/// # let agent = Simulator::new(1, GameMap::new(12, 12), players).to_agent(0);
+/// # let (our_actions, enemy_actions) = (vec![], vec![]);
+/// let mut simulator = Simulator::from_agent(&agent);
+/// simulator.step(&[our_actions, enemy_actions]);
+/// let next_agent = simulator.to_agent(agent.team);
+/// # assert_eq!(next_agent.turn, agent.turn + 1);
+/// # Ok::<(), LuxAiError>(())
+/// ```
+///
+/// # See also
+///
+/// Check <https://www.lux-ai.org/specs-2021>
+#[derive(Clone, fmt::Debug)]
+pub struct Simulator {
+    /// Turn index, counted the same way as [`Agent::turn`]
+    pub turn: TurnAmount,
+
+    /// Whole GameMap
+    ///
+    /// # See also
+    ///
+    /// Check <https://www.lux-ai.org/specs-2021#The%20Map>
+    pub game_map: GameMap,
+
+    /// List of all players participating in match
+    pub players: Vec<Player>,
+
+    next_unit_id: u32,
+    next_city_id: u32,
+}
+
+impl Simulator {
+    /// Creates [`Simulator`] from given state
+    ///
+    /// Ids of new units and cities continue from the highest ids in `players`.
+    /// Referee never reuses ids of dead units and lost cities, so when they are
+    /// known call [`Simulator::reserve_entity_ids`]
+    ///
+    /// # Parameters
+    ///
+    /// - `turn` - turn index, counted the same way as [`Agent::turn`]
+    /// - `game_map` - [`GameMap`] of current turn
+    /// - `players` - all [`players`][Player] participating in match
+    ///
+    /// # Returns
+    ///
+    /// A new created [`Simulator`]
+    pub fn new(turn: TurnAmount, game_map: GameMap, players: Vec<Player>) -> Self {
+        let next_unit_id = players
+            .iter()
+            .flat_map(|player| player.units.iter())
+            .filter_map(|unit| parse_entity_id(&unit.id, UNIT_ID_PREFIX))
+            .max()
+            .map_or(1, |id| id + 1);
+        let next_city_id = players
+            .iter()
+            .flat_map(|player| player.cities.keys())
+            .filter_map(|city_id| parse_entity_id(city_id, CITY_ID_PREFIX))
+            .max()
+            .map_or(1, |id| id + 1);
+
+        let mut simulator = Self {
+            turn,
+            game_map,
+            players,
+            next_unit_id,
+            next_city_id,
+        };
+        for player in simulator.players.iter_mut() {
+            player.city_tile_count = player
+                .cities
+                .values()
+                .map(|city| city.citytiles.len() as u32)
+                .sum();
+        }
+        simulator
+    }
+
+    /// Creates [`Simulator`] from [`Agent`] state, ids of new units and cities
+    /// continue from the highest ids [`Agent::world`] has seen
+    ///
+    /// # Parameters
+    ///
+    /// - `agent` - [`Agent`] reference to copy state from
+    ///
+    /// # Returns
+    ///
+    /// A new created [`Simulator`]
+    pub fn from_agent(agent: &Agent) -> Self {
+        let mut simulator = Self::new(agent.turn, agent.game_map.clone(), agent.players.clone());
+        simulator.reserve_entity_ids(agent.world.max_unit_id, agent.world.max_city_id);
+        simulator
+    }
+
+    /// Makes ids of new units and cities greater than given ones, e.g. ids of
+    /// dead units and lost cities
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - mutable Self reference
+    /// - `max_unit_id` - number of highest [`Unit`] id already used
+    /// - `max_city_id` - number of highest [`City`] id already used
+    ///
+    /// # Returns
+    ///
+    /// Nothing
+    pub fn reserve_entity_ids(&mut self, max_unit_id: u32, max_city_id: u32) {
+        self.next_unit_id = self.next_unit_id.max(max_unit_id + 1);
+        self.next_city_id = self.next_city_id.max(max_city_id + 1);
+    }
+
+    /// Converts current state into [`Agent`] of given team
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    /// - `team` - team id of resulting [`Agent`]
+    ///
+    /// # Returns
+    ///
+    /// A new created [`Agent`]
+    pub fn to_agent(&self, team: TeamId) -> Agent {
+        let simulator = Self::new(self.turn, self.game_map.clone(), self.players.clone());
+        let mut world = WorldModel::new();
+        world.max_unit_id = self.next_unit_id - 1;
+        world.max_city_id = self.next_city_id - 1;
+        Agent {
+            team,
+            turn: simulator.turn,
+            unit_index: UnitIndex::from_players(&simulator.players),
+            game_map: simulator.game_map,
+            players: simulator.players,
+            world,
+            unknown_commands: vec![],
+        }
+    }
+
+    /// Returns [`GameClock`] at current turn
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// [`GameClock`] value
+    pub fn clock(&self) -> GameClock { GameClock::new(self.turn) }
+
+    /// Whether or not current turn is resolved at night
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// `bool` value
+    ///
+    /// # See also
+    ///
+    /// Check <https://www.lux-ai.org/specs-2021#Day/Night%20Cycle>
+    pub fn is_night(&self) -> bool { self.clock().is_night() }
+
+    /// Whether or not match is finished, i.e. all turns are played or any team
+    /// has no units and no city tiles left
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// `bool` value
+    pub fn is_game_over(&self) -> bool {
+        self.clock().is_game_over() ||
+            self.players
+                .iter()
+                .any(|player| player.units.is_empty() && player.city_tile_count == 0)
+    }
+
+    /// Returns team which wins match at current state: the one with more city
+    /// tiles, or with more units in case of draw
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// Team id of winner or `None` on tie
+    ///
+    /// # See also
+    ///
+    /// Check <https://www.lux-ai.org/specs-2021#Win%20Conditions>
+    pub fn winner(&self) -> Option<TeamId> {
+        let scores: Vec<_> = self
+            .players
+            .iter()
+            .map(|player| (player.city_tile_count, player.units.len()))
+            .collect();
+        match scores[0].cmp(&scores[1]) {
+            std::cmp::Ordering::Greater => Some(0),
+            std::cmp::Ordering::Less => Some(1),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+
+    /// Serializes current state into update lines of Lux AI API I/O, the same
+    /// lines [`Agent::update_turn`] reads, ending with [`Commands::DONE`]
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// Update lines of current turn
+    ///
+    /// # See also
+    ///
+    /// Check <https://www.lux-ai.org/specs-2021#Environment>
+    pub fn updates(&self) -> Vec<String> {
+        let mut updates = vec![];
+        for player in self.players.iter() {
+            updates.push(format!(
+                "{} {} {}",
+                Commands::RESEARCH_POINTS,
+                player.team,
+                player.research_points
+            ));
+        }
+        for cell in self.game_map.map.iter().flatten() {
+            if let Some(resource) = cell.resource.as_ref().filter(|_| cell.has_resource()) {
+                updates.push(format!(
+                    "{} {} {} {}",
+                    Commands::RESOURCES,
+                    resource.resource_type.to_argument(),
+                    cell.pos.to_argument(),
+                    resource.amount
+                ));
+            }
+        }
+        for unit in self.players.iter().flat_map(|player| player.units.iter()) {
+            updates.push(format!(
+                "{} {} {} {} {} {} {} {} {}",
+                Commands::UNITS,
+                unit.unit_type.to_argument(),
+                unit.team,
+                unit.id,
+                unit.pos.to_argument(),
+                unit.cooldown,
+                unit.cargo[ResourceType::Wood],
+                unit.cargo[ResourceType::Coal],
+                unit.cargo[ResourceType::Uranium]
+            ));
+        }
+        for player in self.players.iter() {
+            let mut cities: Vec<&City> = player.cities.values().collect();
+            cities.sort_by(|a, b| a.cityid.cmp(&b.cityid));
+            for city in cities {
+                updates.push(format!(
+                    "{} {} {} {} {}",
+                    Commands::CITY,
+                    city.teamid,
+                    city.cityid,
+                    city.fuel,
+                    city.light_upkeep
+                ));
+                for city_tile in city.city_tiles(&self.game_map) {
+                    updates.push(format!(
+                        "{} {} {} {} {}",
+                        Commands::CITY_TILES,
+                        city_tile.teamid,
+                        city_tile.cityid,
+                        city_tile.pos.to_argument(),
+                        city_tile.cooldown
+                    ));
+                }
+            }
+        }
+        for cell in self.game_map.map.iter().flatten() {
+            if cell.road > 0.0 {
+                updates.push(format!(
+                    "{} {} {}",
+                    Commands::ROADS,
+                    cell.pos.to_argument(),
+                    cell.road
+                ));
+            }
+        }
+        updates.push(Commands::DONE.to_string());
+        updates
+    }
+
+    /// Resolves one turn with given actions of all teams
+    ///
+    /// Invalid actions are dropped the same way referee does it: annotations,
+    /// unknown entities, units or city tiles on cooldown, second action of the same
+    /// entity, moves off the map or into enemy city tile and so on
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - mutable Self reference
+    /// - `actions` - actions of each team, indexed by team id
+    ///
+    /// # Returns
+    ///
+    /// Nothing
+    ///
+    /// # See also
+    ///
+    /// Check <https://www.lux-ai.org/specs-2021>
+    pub fn step(&mut self, actions: &[Vec<Action>]) {
+        let is_night = self.is_night();
+        let (unit_orders, city_tile_orders) = self.validate_actions(actions);
+
+        self.resolve_city_tile_orders(city_tile_orders);
+        self.resolve_unit_orders(unit_orders);
+        for resource_type in [
+            ResourceType::Uranium,
+            ResourceType::Coal,
+            ResourceType::Wood,
+        ] {
+            self.distribute_resources(resource_type);
+        }
+        self.deposit_resources();
+        if is_night {
+            self.handle_night();
+        }
+        self.remove_depleted_resources();
+        self.regrow_wood();
+        self.develop_roads();
+        self.run_cooldowns();
+
+        self.turn += 1;
+    }
+
+    fn city_tile_team(&self, position: Position) -> Option<TeamId> {
+        self.game_map
+            .city_tile_at(position)
+            .map(|city_tile| city_tile.teamid)
+    }
+
+    fn validate_actions(&self, actions: &[Vec<Action>]) -> (UnitOrders, CityTileOrders) {
+        let mut unit_orders = vec![];
+        let mut city_tile_orders = vec![];
+        let mut acted_units = HashSet::new();
+        let mut acted_city_tiles = HashSet::new();
+
+        for (team, team_actions) in actions.iter().enumerate().take(TEAM_COUNT as usize) {
+            let team = team as TeamId;
+            let mut units_to_build = 0;
+            for action in team_actions.iter() {
+                if let Some(position) = action.city_tile_position() {
+                    let order = match action {
+                        ActionKind::Research { .. } => CityTileOrder::Research,
+                        ActionKind::BuildWorker { .. } =>
+                            CityTileOrder::BuildUnit(UnitType::Worker),
+                        _ => CityTileOrder::BuildUnit(UnitType::Cart),
+                    };
+                    if !self.can_city_tile_act(team, position) || !acted_city_tiles.insert(position)
+                    {
+                        continue;
+                    }
+                    if let CityTileOrder::BuildUnit(_) = order {
+                        let player = &self.players[team as usize];
+                        if player.units.len() + units_to_build >= player.city_tile_count as usize {
+                            continue;
+                        }
+                        units_to_build += 1;
+                    }
+                    city_tile_orders.push((team, position, order));
+                } else if let Some((index, order)) = self.validate_unit_action(team, action) {
+                    if acted_units.insert((team, index)) {
+                        unit_orders.push((team, index, order));
+                    }
+                }
+            }
+        }
+
+        (unit_orders, city_tile_orders)
+    }
+
+    fn can_city_tile_act(&self, team: TeamId, position: Position) -> bool {
+        if !self.game_map.contains(position) {
+            return false;
+        }
+        self.game_map
+            .city_tile_at(position)
+            .is_some_and(|city_tile| city_tile.teamid == team && city_tile.can_act())
+    }
+
+    fn validate_unit_action(&self, team: TeamId, action: &Action) -> Option<(usize, UnitOrder)> {
+        let unit_id = action.unit_id()?;
+        let player = &self.players[team as usize];
+        let index = player.units.iter().position(|unit| &unit.id == unit_id)?;
+        let unit = &player.units[index];
+        if !unit.can_act() {
+            return None;
+        }
+
+        let order = match action {
+            ActionKind::Move { direction, .. } => {
+                if *direction == Direction::Center {
+                    return None;
+                }
+                let target = self.game_map.translate(unit.pos, *direction, 1)?;
+                if self
+                    .city_tile_team(target)
+                    .is_some_and(|city_team| city_team != team)
+                {
+                    return None;
+                }
+                UnitOrder::Move(target)
+            },
+            ActionKind::Transfer {
+                destination_id,
+                resource_type,
+                amount,
+                ..
+            } => {
+                let destination = player
+                    .units
+                    .iter()
+                    .find(|other| &other.id == destination_id && other.id != unit.id)?;
+                if *amount <= 0 ||
+                    unit.cargo[*resource_type] == 0 ||
+                    destination.get_cargo_space_left() == 0 ||
+                    !unit.pos.is_adjacent(&destination.pos)
+                {
+                    return None;
+                }
+                UnitOrder::Transfer(destination_id.clone(), *resource_type, *amount)
+            },
+            ActionKind::BuildCity { .. } => {
+                let cell = &self.game_map[unit.pos];
+                if !unit.can_build(&self.game_map) || cell.citytile.is_some() {
+                    return None;
+                }
+                UnitOrder::BuildCity
+            },
+            ActionKind::Pillage { .. } => {
+                if !unit.can_pillage(&self.game_map) || self.game_map[unit.pos].citytile.is_some() {
+                    return None;
+                }
+                UnitOrder::Pillage
+            },
+            _ => return None,
+        };
+        Some((index, order))
+    }
+
+    fn resolve_city_tile_orders(&mut self, orders: CityTileOrders) {
+        let cooldown = GAME_CONSTANTS.parameters.city_action_cooldown as Cooldown;
+        for (team, position, order) in orders {
+            match order {
+                CityTileOrder::Research => self.players[team as usize].research_points += 1,
+                CityTileOrder::BuildUnit(unit_type) => {
+                    let id = format!("{}{}", UNIT_ID_PREFIX, self.next_unit_id);
+                    self.next_unit_id += 1;
+                    let unit = Unit::new(team, unit_type, id, position, 0.0);
+                    self.players[team as usize].units.push(unit);
+                },
+            }
+            if let Some(id) = self.game_map[position].citytile {
+                self.game_map[id].cooldown += cooldown;
+            }
+        }
+    }
+
+    fn resolve_unit_orders(&mut self, orders: UnitOrders) {
+        let moves = self.prune_moves(&orders);
+
+        for (team, index, order) in orders.iter() {
+            let (team, index) = (*team, *index);
+            match order {
+                UnitOrder::Move(target) => {
+                    if !moves.contains(&(team, index)) {
+                        continue;
+                    }
+                    self.players[team as usize].units[index].pos = *target;
+                },
+                UnitOrder::Transfer(destination_id, resource_type, amount) => {
+                    let units = &mut self.players[team as usize].units;
+                    let destination = match units.iter().position(|unit| &unit.id == destination_id)
+                    {
+                        Some(destination) => destination,
+                        None => continue,
+                    };
+                    let amount = (*amount)
+                        .min(units[index].cargo[*resource_type])
+                        .min(units[destination].get_cargo_space_left());
+                    units[index].cargo[*resource_type] -= amount;
+                    units[destination].cargo[*resource_type] += amount;
+                },
+                UnitOrder::BuildCity => {
+                    let unit = &mut self.players[team as usize].units[index];
+                    let position = unit.pos;
+                    if self.game_map[position].citytile.is_some() {
+                        continue;
+                    }
+                    unit.cargo = Cargo::default();
+                    self.spawn_city_tile(team, position);
+                },
+                UnitOrder::Pillage => {
+                    let position = self.players[team as usize].units[index].pos;
+                    let parameters = &GAME_CONSTANTS.parameters;
+                    let cell = &mut self.game_map[position];
+                    cell.road = (cell.road - parameters.pillage_rate).max(parameters.min_road);
+                },
+            }
+
+            let unit = &mut self.players[team as usize].units[index];
+            let base_cooldown =
+                GAME_CONSTANTS.parameters.unit_action_cooldown[&unit.unit_type] as Cooldown;
+            let road = self.game_map[unit.pos].road;
+            unit.cooldown = (unit.cooldown + base_cooldown - road).max(1.0);
+        }
+    }
+
+    fn prune_moves(&self, orders: &[(TeamId, usize, UnitOrder)]) -> HashSet<(TeamId, usize)> {
+        let mut targets: HashMap<(TeamId, usize), Position> = orders
+            .iter()
+            .filter_map(|(team, index, order)| match order {
+                UnitOrder::Move(target) => Some(((*team, *index), *target)),
+                _ => None,
+            })
+            .collect();
+
+        loop {
+            let mut occupancy: HashMap<Position, Vec<(TeamId, usize)>> = HashMap::new();
+            for player in self.players.iter() {
+                for (index, unit) in player.units.iter().enumerate() {
+                    let key = (player.team, index);
+                    let position = targets.get(&key).cloned().unwrap_or(unit.pos);
+                    occupancy.entry(position).or_default().push(key);
+                }
+            }
+
+            let cancelled: Vec<_> = targets
+                .iter()
+                .filter(|(key, target)| {
+                    let crowded =
+                        occupancy[*target].len() > 1 && self.game_map[**target].citytile.is_none();
+                    let origin = self.players[key.0 as usize].units[key.1].pos;
+                    let swapped = targets.iter().any(|(other, other_target)| {
+                        let other_position = self.players[other.0 as usize].units[other.1].pos;
+                        other_position == **target && *other_target == origin
+                    });
+                    crowded || swapped
+                })
+                .map(|(key, _)| *key)
+                .collect();
+
+            if cancelled.is_empty() {
+                break;
+            }
+            for key in cancelled {
+                targets.remove(&key);
+            }
+        }
+
+        targets.into_keys().collect()
+    }
+
+    fn spawn_city_tile(&mut self, team: TeamId, position: Position) {
+        let player = &mut self.players[team as usize];
+        let adjacent_city_ids = player.adjacent_city_ids(&self.game_map, position);
+        let city_id = match adjacent_city_ids.first() {
+            Some(city_id) => city_id.clone(),
+            None => {
+                let city_id = format!("{}{}", CITY_ID_PREFIX, self.next_city_id);
+                self.next_city_id += 1;
+                player
+                    .cities
+                    .insert(city_id.clone(), City::new(team, city_id.clone(), 0.0, 0.0));
+                city_id
+            },
+        };
+
+        for merged_id in adjacent_city_ids.iter().skip(1) {
+            if let Some(merged) = player.cities.remove(merged_id) {
+                let city = player.cities.get_mut(&city_id).unwrap();
+                city.fuel += merged.fuel;
+                for id in merged.citytiles {
+                    self.game_map[id].cityid = city_id.clone();
+                    city.citytiles.push(id);
+                }
+            }
+        }
+
+        let city = player.cities.get_mut(&city_id).unwrap();
+        city.add_city_tile(&mut self.game_map, position, 0.0);
+        player.city_tile_count += 1;
+        self.game_map[position].road = GAME_CONSTANTS.parameters.max_road;
+
+        self.update_light_upkeep(team, &city_id);
+    }
+
+    fn update_light_upkeep(&mut self, team: TeamId, city_id: &str) {
+        if let Some(city) = self.players[team as usize].cities.get_mut(city_id) {
+            city.light_upkeep = city.compute_light_upkeep(&self.game_map);
+        }
+    }
+
+    fn distribute_resources(&mut self, resource_type: ResourceType) {
+        let collection_rate = GAME_CONSTANTS.parameters.worker_collection_rate[&resource_type];
+        let mut workers_by_position: HashMap<Position, Vec<(TeamId, usize)>> = HashMap::new();
+        for player in self.players.iter() {
+            if !player.is_researched(resource_type) {
+                continue;
+            }
+            for (index, unit) in player.units.iter().enumerate() {
+                if unit.unit_type == UnitType::Worker {
+                    workers_by_position
+                        .entry(unit.pos)
+                        .or_default()
+                        .push((player.team, index));
+                }
+            }
+        }
+
+        for y in 0..self.game_map.height {
+            for x in 0..self.game_map.width {
+                let position = Position::new(x, y);
+                let amount = match self.game_map[position].resource.as_ref() {
+                    Some(resource) if resource.resource_type == resource_type => resource.amount,
+                    _ => continue,
+                };
+                if amount <= 0 {
+                    continue;
+                }
+
+                let mut requests: Vec<_> = std::iter::once(position)
+                    .chain(self.game_map.neighbors(position))
+                    .filter_map(|position| workers_by_position.get(&position))
+                    .flatten()
+                    .map(|(team, index)| {
+                        let unit = &self.players[*team as usize].units[*index];
+                        (
+                            unit.get_cargo_space_left().min(collection_rate),
+                            *team,
+                            *index,
+                        )
+                    })
+                    .filter(|(request, ..)| *request > 0)
+                    .collect();
+                requests.sort_by_key(|(request, ..)| *request);
+
+                let mut remaining = amount;
+                let count = requests.len() as ResourceAmount;
+                for (served, (request, team, index)) in requests.into_iter().enumerate() {
+                    let share = remaining / (count - served as ResourceAmount);
+                    let collected = request.min(share);
+                    remaining -= collected;
+                    self.players[team as usize].units[index].cargo[resource_type] += collected;
+                }
+
+                if let Some(resource) = self.game_map[position].resource.as_mut() {
+                    resource.amount = remaining;
+                }
+            }
+        }
+    }
+
+    fn deposit_resources(&mut self) {
+        let fuel_rates = &GAME_CONSTANTS.parameters.resource_to_fuel_rate;
+        for player in self.players.iter_mut() {
+            for unit in player.units.iter_mut() {
+                let city_id = match self.game_map.city_tile_at(unit.pos) {
+                    Some(city_tile) if city_tile.teamid == player.team => city_tile.cityid.clone(),
+                    _ => continue,
+                };
+                let fuel: FuelAmount = ResourceType::VALUES
+                    .into_iter()
+                    .map(|resource_type| {
+                        unit.cargo[resource_type] as FuelAmount * fuel_rates[&resource_type]
+                    })
+                    .sum();
+                if let Some(city) = player.cities.get_mut(&city_id) {
+                    city.fuel += fuel;
+                    unit.cargo = Cargo::default();
+                }
+            }
+        }
+    }
+
+    fn handle_night(&mut self) {
+        for player in self.players.iter_mut() {
+            let dark_city_ids: Vec<_> = player
+                .cities
+                .values()
+                .filter(|city| city.fuel < city.light_upkeep)
+                .map(|city| city.cityid.clone())
+                .collect();
+            for city in player.cities.values_mut() {
+                if city.fuel >= city.light_upkeep {
+                    city.fuel -= city.light_upkeep;
+                }
+            }
+            for city_id in dark_city_ids {
+                if let Some(city) = player.cities.remove(&city_id) {
+                    for id in city.citytiles.iter() {
+                        self.game_map.remove_city_tile(*id);
+                    }
+                    player.city_tile_count -= city.citytiles.len() as u32;
+                }
+            }
+        }
+
+        for player in self.players.iter_mut() {
+            let game_map = &self.game_map;
+            player.units.retain_mut(|unit| {
+                game_map[unit.pos].citytile.is_some() || unit.spend_fuel_to_survive()
+            });
+        }
+    }
+
+    fn remove_depleted_resources(&mut self) {
+        for row in self.game_map.map.iter_mut() {
+            for cell in row.iter_mut() {
+                if !cell.has_resource() {
+                    cell.resource = None;
+                }
+            }
+        }
+    }
+
+    fn regrow_wood(&mut self) {
+        let parameters = &GAME_CONSTANTS.parameters;
+        for row in self.game_map.map.iter_mut() {
+            for cell in row.iter_mut() {
+                if let Some(resource) = cell.resource.as_mut() {
+                    if resource.resource_type == ResourceType::Wood &&
+                        resource.amount < parameters.max_wood_amount
+                    {
+                        let grown = (resource.amount as f32 * parameters.wood_growth_rate).ceil();
+                        resource.amount = (grown as ResourceAmount).min(parameters.max_wood_amount);
+                    }
+                }
+            }
+        }
+    }
+
+    fn develop_roads(&mut self) {
+        let parameters = &GAME_CONSTANTS.parameters;
+        for player in self.players.iter() {
+            for unit in player.units.iter() {
+                if unit.unit_type == UnitType::Cart {
+                    let cell = &mut self.game_map[unit.pos];
+                    cell.road = (cell.road + parameters.cart_road_development_rate)
+                        .min(parameters.max_road);
+                }
+            }
+        }
+    }
+
+    fn run_cooldowns(&mut self) {
+        for player in self.players.iter_mut() {
+            for unit in player.units.iter_mut() {
+                unit.cooldown = (unit.cooldown - 1.0).max(0.0);
+            }
+            for id in player
+                .cities
+                .values()
+                .flat_map(|city| city.citytiles.iter())
+            {
+                let city_tile = &mut self.game_map[*id];
+                city_tile.cooldown = (city_tile.cooldown - 1.0).max(0.0);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIRST_NIGHT_TURN: TurnAmount = 31;
+
+    fn simulator() -> Simulator {
+        Simulator::new(1, GameMap::new(8, 8), vec![Player::new(0), Player::new(1)])
+    }
+
+    fn add_unit(
+        simulator: &mut Simulator, team: TeamId, unit_type: UnitType, id: &str, x: i32, y: i32,
+    ) -> Unit {
+        let unit = Unit::new(team, unit_type, id.to_string(), Position::new(x, y), 0.0);
+        simulator.players[team as usize].units.push(unit.clone());
+        unit
+    }
+
+    fn add_city(
+        simulator: &mut Simulator, team: TeamId, id: &str, fuel: FuelAmount, x: i32, y: i32,
+    ) {
+        let mut city = City::new(team, id.to_string(), fuel, 0.0);
+        city.add_city_tile(&mut simulator.game_map, Position::new(x, y), 0.0);
+        let player = &mut simulator.players[team as usize];
+        player.cities.insert(city.cityid.clone(), city);
+        player.city_tile_count += 1;
+        simulator.update_light_upkeep(team, id);
+    }
+
+    fn add_resource(
+        simulator: &mut Simulator, resource_type: ResourceType, amount: i32, x: i32, y: i32,
+    ) {
+        simulator.game_map[Position::new(x, y)].resource =
+            Some(Resource::new(resource_type, amount));
+    }
+
+    fn unit<'a>(simulator: &'a Simulator, team: TeamId, id: &str) -> &'a Unit {
+        simulator.players[team as usize]
+            .units
+            .iter()
+            .find(|unit| unit.id == id)
+            .unwrap()
+    }
+
+    #[test]
+    fn moves_into_same_cell_are_cancelled() {
+        let mut simulator = simulator();
+        let first = add_unit(&mut simulator, 0, UnitType::Worker, "u_1", 1, 2);
+        let second = add_unit(&mut simulator, 1, UnitType::Worker, "u_2", 3, 2);
+        let third = add_unit(&mut simulator, 0, UnitType::Worker, "u_3", 5, 5);
+
+        simulator.step(&[
+            vec![first.move_(Direction::East), third.move_(Direction::North)],
+            vec![second.move_(Direction::West)],
+        ]);
+
+        assert_eq!(unit(&simulator, 0, "u_1").pos, Position::new(1, 2));
+        assert_eq!(unit(&simulator, 1, "u_2").pos, Position::new(3, 2));
+        assert_eq!(unit(&simulator, 0, "u_3").pos, Position::new(5, 4));
+    }
+
+    #[test]
+    fn moves_off_map_are_dropped() {
+        let mut simulator = simulator();
+        let first = add_unit(&mut simulator, 0, UnitType::Worker, "u_1", 0, 0);
+        let second = add_unit(&mut simulator, 1, UnitType::Worker, "u_2", 7, 7);
+
+        simulator.step(&[
+            vec![first.move_(Direction::West)],
+            vec![second.move_(Direction::South)],
+        ]);
+
+        assert_eq!(unit(&simulator, 0, "u_1").pos, Position::new(0, 0));
+        assert_eq!(unit(&simulator, 0, "u_1").cooldown, 0.0);
+        assert_eq!(unit(&simulator, 1, "u_2").pos, Position::new(7, 7));
+    }
+
+    #[test]
+    fn swapping_units_are_cancelled() {
+        let mut simulator = simulator();
+        let first = add_unit(&mut simulator, 0, UnitType::Worker, "u_1", 1, 1);
+        let second = add_unit(&mut simulator, 0, UnitType::Worker, "u_2", 2, 1);
+
+        simulator.step(&[
+            vec![first.move_(Direction::East), second.move_(Direction::West)],
+            vec![],
+        ]);
+
+        assert_eq!(unit(&simulator, 0, "u_1").pos, Position::new(1, 1));
+        assert_eq!(unit(&simulator, 0, "u_2").pos, Position::new(2, 1));
+    }
+
+    #[test]
+    fn units_stack_on_own_city_tile() {
+        let mut simulator = simulator();
+        add_city(&mut simulator, 0, "c_1", 100.0, 2, 2);
+        let first = add_unit(&mut simulator, 0, UnitType::Worker, "u_1", 1, 2);
+        let second = add_unit(&mut simulator, 0, UnitType::Worker, "u_2", 3, 2);
+        let enemy = add_unit(&mut simulator, 1, UnitType::Worker, "u_3", 2, 1);
+
+        simulator.step(&[
+            vec![first.move_(Direction::East), second.move_(Direction::West)],
+            vec![enemy.move_(Direction::South)],
+        ]);
+
+        assert_eq!(unit(&simulator, 0, "u_1").pos, Position::new(2, 2));
+        assert_eq!(unit(&simulator, 0, "u_2").pos, Position::new(2, 2));
+        assert_eq!(unit(&simulator, 1, "u_3").pos, Position::new(2, 1));
+    }
+
+    #[test]
+    fn resources_are_distributed_uranium_first() {
+        let mut simulator = simulator();
+        simulator.players[0].research_points = ResourceType::Uranium.required_research_points();
+        add_resource(&mut simulator, ResourceType::Wood, 400, 3, 2);
+        add_resource(&mut simulator, ResourceType::Coal, 400, 2, 1);
+        add_resource(&mut simulator, ResourceType::Uranium, 400, 1, 2);
+        let mut worker = add_unit(&mut simulator, 0, UnitType::Worker, "u_1", 2, 2);
+        worker.cargo.wood = worker.get_cargo_space_left() - 6;
+        simulator.players[0].units[0] = worker.clone();
+
+        simulator.step(&[vec![], vec![]]);
+
+        let rates = &GAME_CONSTANTS.parameters.worker_collection_rate;
+        let cargo = unit(&simulator, 0, "u_1").cargo;
+        assert_eq!(cargo.uranium, rates[&ResourceType::Uranium]);
+        assert_eq!(cargo.coal, 6 - rates[&ResourceType::Uranium]);
+        assert_eq!(cargo.wood, worker.cargo.wood);
+    }
+
+    #[test]
+    fn resources_require_research() {
+        let mut simulator = simulator();
+        add_resource(&mut simulator, ResourceType::Coal, 400, 2, 1);
+        add_resource(&mut simulator, ResourceType::Wood, 400, 3, 2);
+        add_unit(&mut simulator, 0, UnitType::Worker, "u_1", 2, 2);
+
+        simulator.step(&[vec![], vec![]]);
+
+        let cargo = unit(&simulator, 0, "u_1").cargo;
+        assert_eq!(cargo.coal, 0);
+        assert_eq!(
+            cargo.wood,
+            GAME_CONSTANTS.parameters.worker_collection_rate[&ResourceType::Wood]
+        );
+    }
+
+    #[test]
+    fn city_burns_light_upkeep_at_night() {
+        let mut simulator = simulator();
+        simulator.turn = FIRST_NIGHT_TURN;
+        assert!(simulator.is_night());
+        add_city(&mut simulator, 0, "c_1", 30.0, 2, 2);
+        let light_upkeep = simulator.players[0].cities["c_1"].light_upkeep;
+
+        simulator.step(&[vec![], vec![]]);
+
+        assert_eq!(simulator.players[0].cities["c_1"].fuel, 30.0 - light_upkeep);
+    }
+
+    #[test]
+    fn city_without_fuel_is_lost_at_night() {
+        let mut simulator = simulator();
+        simulator.turn = FIRST_NIGHT_TURN;
+        add_city(&mut simulator, 0, "c_1", 10.0, 2, 2);
+        add_unit(&mut simulator, 0, UnitType::Worker, "u_1", 2, 2);
+
+        simulator.step(&[vec![], vec![]]);
+
+        assert!(simulator.players[0].cities.is_empty());
+        assert_eq!(simulator.players[0].city_tile_count, 0);
+        assert_eq!(simulator.city_tile_team(Position::new(2, 2)), None);
+        assert!(simulator.players[0].units.is_empty());
+    }
+
+    #[test]
+    fn units_spend_cargo_to_survive_night() {
+        let mut simulator = simulator();
+        simulator.turn = FIRST_NIGHT_TURN;
+        let mut worker = add_unit(&mut simulator, 0, UnitType::Worker, "u_1", 2, 2);
+        worker.cargo.wood = 10;
+        simulator.players[0].units[0] = worker;
+        add_unit(&mut simulator, 0, UnitType::Worker, "u_2", 5, 5);
+
+        simulator.step(&[vec![], vec![]]);
+
+        let upkeep = GAME_CONSTANTS.parameters.light_upkeep[&ObjectType::Unit(UnitType::Worker)];
+        assert_eq!(simulator.players[0].units.len(), 1);
+        assert_eq!(
+            unit(&simulator, 0, "u_1").cargo.wood,
+            10 - upkeep as ResourceAmount
+        );
+    }
+
+    #[test]
+    fn wood_regrows_up_to_max_amount() {
+        let mut simulator = simulator();
+        let max_wood_amount = GAME_CONSTANTS.parameters.max_wood_amount;
+        add_resource(&mut simulator, ResourceType::Wood, 100, 1, 1);
+        add_resource(
+            &mut simulator,
+            ResourceType::Wood,
+            max_wood_amount - 1,
+            3,
+            3,
+        );
+        add_resource(&mut simulator, ResourceType::Wood, max_wood_amount, 5, 5);
+
+        simulator.step(&[vec![], vec![]]);
+
+        let amount = |x, y| {
+            simulator.game_map[Position::new(x, y)]
+                .resource
+                .as_ref()
+                .unwrap()
+                .amount
+        };
+        assert_eq!(amount(1, 1), 103);
+        assert_eq!(amount(3, 3), max_wood_amount);
+        assert_eq!(amount(5, 5), max_wood_amount);
+    }
+
+    #[test]
+    fn actions_set_cooldown_reduced_by_road() {
+        let mut simulator = simulator();
+        simulator.game_map[Position::new(4, 3)].road = GAME_CONSTANTS.parameters.max_road;
+        let first = add_unit(&mut simulator, 0, UnitType::Worker, "u_1", 1, 2);
+        let second = add_unit(&mut simulator, 0, UnitType::Worker, "u_2", 4, 2);
+
+        simulator.step(&[
+            vec![first.move_(Direction::East), second.move_(Direction::South)],
+            vec![],
+        ]);
+
+        assert_eq!(unit(&simulator, 0, "u_1").cooldown, 1.0);
+        assert_eq!(unit(&simulator, 0, "u_2").cooldown, 0.0);
+
+        let first = unit(&simulator, 0, "u_1").clone();
+        assert!(!first.can_act());
+        simulator.step(&[vec![first.move_(Direction::East)], vec![]]);
+        assert_eq!(unit(&simulator, 0, "u_1").pos, Position::new(2, 2));
+    }
+
+    #[test]
+    fn carts_develop_roads() {
+        let mut simulator = simulator();
+        add_unit(&mut simulator, 0, UnitType::Cart, "u_1", 1, 1);
+
+        for _ in 0..10 {
+            simulator.step(&[vec![], vec![]]);
+        }
+
+        assert_eq!(
+            simulator.game_map[Position::new(1, 1)].road,
+            GAME_CONSTANTS.parameters.max_road
+        );
+    }
+
+    #[test]
+    fn built_city_tile_has_max_road() {
+        let mut simulator = simulator();
+        let mut worker = add_unit(&mut simulator, 0, UnitType::Worker, "u_1", 3, 3);
+        worker.cargo.wood = GAME_CONSTANTS.parameters.city_build_cost as ResourceAmount;
+        simulator.players[0].units[0] = worker.clone();
+
+        simulator.step(&[vec![worker.build_city()], vec![]]);
+
+        let position = Position::new(3, 3);
+        assert_eq!(simulator.city_tile_team(position), Some(0));
+        assert_eq!(
+            simulator.game_map[position].road,
+            GAME_CONSTANTS.parameters.max_road
+        );
+        assert_eq!(simulator.players[0].city_tile_count, 1);
+    }
+
+    #[test]
+    fn coordinated_moves_are_not_cancelled() {
+        let mut simulator = simulator();
+        let requests = [
+            // swap
+            add_unit(&mut simulator, 0, UnitType::Worker, "u_1", 0, 0).move_(Direction::East),
+            add_unit(&mut simulator, 0, UnitType::Worker, "u_2", 1, 0).move_(Direction::West),
+            // two units into one cell outside of city
+            add_unit(&mut simulator, 0, UnitType::Worker, "u_3", 4, 0).move_(Direction::South),
+            add_unit(&mut simulator, 0, UnitType::Worker, "u_4", 4, 2).move_(Direction::North),
+            // three units rotating through a free cell
+            add_unit(&mut simulator, 0, UnitType::Worker, "u_5", 0, 3).move_(Direction::East),
+            add_unit(&mut simulator, 0, UnitType::Worker, "u_6", 1, 3).move_(Direction::South),
+            add_unit(&mut simulator, 0, UnitType::Worker, "u_7", 1, 4).move_(Direction::West),
+            // four units rotating without a free cell
+            add_unit(&mut simulator, 0, UnitType::Worker, "u_8", 4, 4).move_(Direction::East),
+            add_unit(&mut simulator, 0, UnitType::Worker, "u_9", 5, 4).move_(Direction::South),
+            add_unit(&mut simulator, 0, UnitType::Worker, "u_10", 5, 5).move_(Direction::West),
+            add_unit(&mut simulator, 0, UnitType::Worker, "u_11", 4, 5).move_(Direction::North),
+        ];
+
+        let mut coordinator = MoveCoordinator::new(&simulator.game_map, &simulator.players, 0);
+        for request in requests.iter() {
+            if let ActionKind::Move { unit_id, direction } = request {
+                coordinator.request(unit(&simulator, 0, unit_id), *direction);
+            }
+        }
+        let resolution = coordinator.resolve();
+
+        let granted: Vec<_> = resolution
+            .actions
+            .iter()
+            .filter_map(|action| action.unit_id().cloned())
+            .collect();
+        assert_eq!(granted, ["u_3", "u_5", "u_6", "u_7"]);
+
+        let (orders, _) = simulator.validate_actions(&[resolution.actions, vec![]]);
+        assert_eq!(orders.len(), granted.len());
+        assert_eq!(simulator.prune_moves(&orders).len(), granted.len());
+    }
+
+    #[test]
+    fn city_build_merges_cities_as_predicted() {
+        let mut simulator = simulator();
+        add_city(&mut simulator, 0, "c_1", 40.0, 1, 1);
+        add_city(&mut simulator, 0, "c_2", 60.0, 3, 1);
+        let mut worker = add_unit(&mut simulator, 0, UnitType::Worker, "u_1", 2, 1);
+        worker.cargo.wood = 100;
+        simulator.players[0].units[0] = worker.clone();
+        let prediction = simulator.players[0].predict_city_build(&simulator.game_map, worker.pos);
+
+        simulator.step(&[vec![worker.build_city()], vec![]]);
+
+        let player = &simulator.players[0];
+        assert_eq!(player.cities.len(), 1);
+        let city = &player.cities[prediction.city_id.as_ref().unwrap()];
+        assert_eq!(
+            city.city_tiles(&simulator.game_map).count(),
+            prediction.city_tiles
+        );
+        assert_eq!(city.fuel, prediction.fuel);
+        assert_eq!(city.light_upkeep, prediction.light_upkeep);
+        assert_eq!(city.light_upkeep, 49.0);
+    }
+}
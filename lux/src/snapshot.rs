@@ -0,0 +1,299 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+/// Serializable state of [`City`], city tiles are stored by value
+#[derive(Clone, PartialEq, fmt::Debug, Serialize, Deserialize)]
+pub struct CitySnapshot {
+    /// City id used as command arguments
+    pub cityid: EntityId,
+
+    /// Fuel amount of [`City`]
+    pub fuel: FuelAmount,
+
+    /// Fuel burnt by [`City`] every night turn
+    pub light_upkeep: FuelAmount,
+
+    /// [`CityTiles`][CityTile] of [`City`]
+    pub citytiles: Vec<CityTile>,
+}
+
+/// Serializable state of [`Player`]
+#[derive(Clone, PartialEq, fmt::Debug, Serialize, Deserialize)]
+pub struct PlayerSnapshot {
+    /// [`Player`]'s team
+    pub team: TeamId,
+
+    /// Researched points of [`Player`]
+    pub research_points: ResearchPointAmount,
+
+    /// [`Units`][Unit] of [`Player`]
+    pub units: Vec<Unit>,
+
+    /// [`Cities`][City] of [`Player`], ordered by id
+    pub cities: Vec<CitySnapshot>,
+}
+
+/// Serializable state of [`Cell`] with resource or road, empty cells are
+/// omitted
+#[derive(Clone, PartialEq, fmt::Debug, Serialize, Deserialize)]
+pub struct CellSnapshot {
+    /// [`Position`] of [`Cell`]
+    pub pos: Position,
+
+    /// [`Resource`] on [`Cell`]
+    pub resource: Option<Resource>,
+
+    /// Road development progress of [`Cell`]
+    pub road: RoadAmount,
+}
+
+/// Serializable snapshot of whole [`Agent`] game state
+///
+/// [`WorldModel`] history is not part of snapshot, [`Agent`] restored from
+/// snapshot starts tracking units and cities from its turn
+///
+/// # Examples
+///
+/// ```
+/// # use lux_ai_api::*;
+/// # let agent = MapGenerator::new(1, 12)?.generate().to_agent(0);
+/// let json = serde_json::to_string(&AgentSnapshot::from_agent(&agent))?;
+/// let snapshot: AgentSnapshot = serde_json::from_str(&json)?;
+/// let restored = snapshot.to_agent()?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Clone, PartialEq, fmt::Debug, Serialize, Deserialize)]
+pub struct AgentSnapshot {
+    /// Team id of [`Agent`]
+    pub team: TeamId,
+
+    /// Turn index of [`Agent`]
+    pub turn: TurnAmount,
+
+    /// Width of [`GameMap`]
+    pub width: Coordinate,
+
+    /// Height of [`GameMap`]
+    pub height: Coordinate,
+
+    /// All players participating in match
+    pub players: Vec<PlayerSnapshot>,
+
+    /// Cells with resource or road, row by row
+    pub cells: Vec<CellSnapshot>,
+}
+
+impl AgentSnapshot {
+    /// Creates [`AgentSnapshot`] of [`Agent`] state
+    ///
+    /// # Parameters
+    ///
+    /// - `agent` - [`Agent`] reference
+    ///
+    /// # Returns
+    ///
+    /// A new created [`AgentSnapshot`]
+    pub fn from_agent(agent: &Agent) -> Self {
+        let players = agent
+            .players
+            .iter()
+            .map(|player| {
+                let mut cities: Vec<CitySnapshot> = player
+                    .cities
+                    .values()
+                    .map(|city| CitySnapshot {
+                        cityid:       city.cityid.clone(),
+                        fuel:         city.fuel,
+                        light_upkeep: city.light_upkeep,
+                        citytiles:    city.city_tiles(&agent.game_map).cloned().collect(),
+                    })
+                    .collect();
+                cities.sort_by(|a, b| a.cityid.cmp(&b.cityid));
+                PlayerSnapshot {
+                    team: player.team,
+                    research_points: player.research_points,
+                    units: player.units.clone(),
+                    cities,
+                }
+            })
+            .collect();
+        let cells = agent
+            .game_map
+            .map
+            .iter()
+            .flatten()
+            .filter(|cell| cell.resource.is_some() || cell.road != 0.0)
+            .map(|cell| CellSnapshot {
+                pos:      cell.pos,
+                resource: cell.resource.clone(),
+                road:     cell.road,
+            })
+            .collect();
+
+        Self {
+            team: agent.team,
+            turn: agent.turn,
+            width: agent.game_map.width,
+            height: agent.game_map.height,
+            players,
+            cells,
+        }
+    }
+
+    /// Restores [`Agent`] from snapshot
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// A new created [`Agent`] with empty [`WorldModel`] or error if map has
+    /// no cells or snapshot has positions outside of map
+    pub fn to_agent(&self) -> LuxAiResult<Agent> {
+        if self.width <= 0 || self.height <= 0 {
+            return Err(LuxAiError::SnapshotFormat(format!(
+                "Map size {}x{}",
+                self.width, self.height
+            )));
+        }
+        let mut game_map = GameMap::new(self.width, self.height);
+        for cell in self.cells.iter() {
+            let game_map_cell = Self::cell_mut(&mut game_map, cell.pos)?;
+            game_map_cell.resource = cell.resource.clone();
+            game_map_cell.road = cell.road;
+        }
+
+        let mut players = vec![];
+        for snapshot in self.players.iter() {
+            let mut player = Player::new(snapshot.team);
+            player.research_points = snapshot.research_points;
+            for unit in snapshot.units.iter() {
+                Self::cell_mut(&mut game_map, unit.pos)?;
+            }
+            player.units = snapshot.units.clone();
+            for city_snapshot in snapshot.cities.iter() {
+                let mut city = City::new(
+                    snapshot.team,
+                    city_snapshot.cityid.clone(),
+                    city_snapshot.fuel,
+                    city_snapshot.light_upkeep,
+                );
+                for city_tile in city_snapshot.citytiles.iter() {
+                    if Self::cell_mut(&mut game_map, city_tile.pos)?
+                        .citytile
+                        .is_some()
+                    {
+                        return Err(LuxAiError::SnapshotFormat(format!(
+                            "Second city tile at {}",
+                            city_tile.pos
+                        )));
+                    }
+                    city.add_city_tile(&mut game_map, city_tile.pos, city_tile.cooldown);
+                }
+                player.city_tile_count += city.citytiles.len() as u32;
+                player.cities.insert(city.cityid.clone(), city);
+            }
+            players.push(player);
+        }
+
+        let mut agent = Agent {
+            team: self.team,
+            turn: self.turn,
+            game_map,
+            players,
+            world: WorldModel::new(),
+            unit_index: UnitIndex::new(),
+            unknown_commands: vec![],
+        };
+        agent.unit_index.update(&agent.players);
+        Ok(agent)
+    }
+
+    fn cell_mut(game_map: &mut GameMap, position: Position) -> LuxAiResult<&mut Cell> {
+        game_map.get_mut(position).ok_or_else(|| {
+            LuxAiError::SnapshotFormat(format!("Position {} is outside of map", position))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn agent() -> Agent {
+        let mut agent = MapGenerator::new(5, 12).unwrap().generate().to_agent(1);
+        agent.turn = 17;
+        agent.players[0].research_points = 42;
+        agent.players[1].units[0].cargo.coal = 12;
+        agent.players[1].units[0].cooldown = 1.5;
+        agent.game_map[Position::new(3, 4)].road = 0.75;
+        agent
+    }
+
+    #[test]
+    fn agent_round_trips_through_json() {
+        let agent = agent();
+        let snapshot = AgentSnapshot::from_agent(&agent);
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let restored = serde_json::from_str::<AgentSnapshot>(&json)
+            .unwrap()
+            .to_agent()
+            .unwrap();
+
+        assert_eq!(AgentSnapshot::from_agent(&restored), snapshot);
+        assert_eq!((restored.team, restored.turn), (1, 17));
+        assert_eq!(restored.game_map.dimensions::<Coordinate>(), (12, 12));
+        for (player, restored_player) in agent.players.iter().zip(restored.players.iter()) {
+            assert_eq!(restored_player.units, player.units);
+            assert_eq!(restored_player.research_points, player.research_points);
+            assert_eq!(restored_player.city_tile_count, player.city_tile_count);
+        }
+        for (row, restored_row) in agent.game_map.map.iter().zip(restored.game_map.map.iter()) {
+            for (cell, restored_cell) in row.iter().zip(restored_row.iter()) {
+                assert_eq!(restored_cell.resource, cell.resource);
+                assert_eq!(restored_cell.road, cell.road);
+                assert_eq!(
+                    restored.game_map.city_tile_at(restored_cell.pos),
+                    agent.game_map.city_tile_at(cell.pos)
+                );
+            }
+        }
+        assert_eq!(restored.unit_index.len(), 2);
+    }
+
+    #[test]
+    fn positions_outside_of_map_are_rejected() {
+        let mut snapshot = AgentSnapshot::from_agent(&agent());
+        snapshot.players[0].units[0].pos = Position::new(12, 0);
+        assert!(matches!(
+            snapshot.to_agent(),
+            Err(LuxAiError::SnapshotFormat(_))
+        ));
+
+        let mut snapshot = AgentSnapshot::from_agent(&agent());
+        snapshot.players[1].cities[0].citytiles[0].pos = Position::new(0, -1);
+        assert!(matches!(
+            snapshot.to_agent(),
+            Err(LuxAiError::SnapshotFormat(_))
+        ));
+
+        let mut snapshot = AgentSnapshot::from_agent(&agent());
+        snapshot.cells[0].pos = Position::new(40, 40);
+        assert!(matches!(
+            snapshot.to_agent(),
+            Err(LuxAiError::SnapshotFormat(_))
+        ));
+
+        let mut snapshot = AgentSnapshot::from_agent(&agent());
+        snapshot.width = 0;
+        assert!(matches!(
+            snapshot.to_agent(),
+            Err(LuxAiError::SnapshotFormat(_))
+        ));
+    }
+}
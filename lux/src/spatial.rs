@@ -0,0 +1,266 @@
+use std::{collections::HashMap, fmt};
+
+use crate::*;
+
+/// Per-turn spatial index of units of both teams by [`Position`]
+///
+/// Units are stored as copies, so index stays valid while [`Agent`] is being
+/// mutated
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// # use lux_ai_api::*;
+/// # let input = "0\n12 12\nu 0 0 u_1 3 3 0 0 0 0\nD_DONE\n";
+/// # let mut environment = Environment::with_io(io::Cursor::new(input), Vec::new());
+/// # let mut agent = Agent::new(&mut environment)?;
+/// agent.update_turn(&mut environment)?;
+/// # let unit = &agent.player().units[0];
+/// let enemies = agent
+///     .unit_index
+///     .units_in_radius(unit.pos, 1)
+///     .into_iter()
+///     .filter(|other| other.team != agent.team);
+/// let worker = agent.unit_index.nearest(unit.pos, Some(agent.team), Some(UnitType::Worker));
+/// # Ok::<(), LuxAiError>(())
+/// ```
+///
+/// # See also
+///
+/// Check <https://www.lux-ai.org/specs-2021#Units>
+#[derive(Clone, Default, fmt::Debug)]
+pub struct UnitIndex {
+    cells: HashMap<Position, Vec<Unit>>,
+}
+
+impl UnitIndex {
+    /// Creates empty [`UnitIndex`]
+    ///
+    /// # Parameters
+    ///
+    /// None
+    ///
+    /// # Returns
+    ///
+    /// A new created [`UnitIndex`] without units
+    pub fn new() -> Self { Self::default() }
+
+    /// Creates [`UnitIndex`] of all units of given players
+    ///
+    /// # Parameters
+    ///
+    /// - `players` - all [`players`][Player] participating in match
+    ///
+    /// # Returns
+    ///
+    /// A new created [`UnitIndex`]
+    pub fn from_players(players: &[Player]) -> Self {
+        let mut index = Self::new();
+        index.update(players);
+        index
+    }
+
+    /// Rebuilds index from units of given players
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - mutable Self reference
+    /// - `players` - all [`players`][Player] participating in match
+    ///
+    /// # Returns
+    ///
+    /// Nothing
+    pub fn update(&mut self, players: &[Player]) {
+        self.cells.clear();
+        for unit in players.iter().flat_map(|player| player.units.iter()) {
+            self.cells.entry(unit.pos).or_default().push(unit.clone());
+        }
+    }
+
+    /// Returns units standing on `position`
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    /// - `position` - [`Position`] of cell
+    ///
+    /// # Returns
+    ///
+    /// Units on the cell, empty if there is no unit
+    pub fn units_at(&self, position: Position) -> &[Unit] {
+        self.cells
+            .get(&position)
+            .map(|units| units.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Whether or not any unit stands on `position`
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    /// - `position` - [`Position`] of cell
+    ///
+    /// # Returns
+    ///
+    /// `bool` value
+    pub fn is_occupied(&self, position: Position) -> bool { !self.units_at(position).is_empty() }
+
+    /// Returns units within Manhattan distance `radius` from `center`
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    /// - `center` - [`Position`] to measure from
+    /// - `radius` - max distance in moves, `1` includes adjacent cells
+    ///
+    /// # Returns
+    ///
+    /// Units ordered by distance from `center`
+    pub fn units_in_radius(&self, center: Position, radius: Coordinate) -> Vec<&Unit> {
+        let mut units = vec![];
+        for dy in -radius..=radius {
+            let width = radius - dy.abs();
+            for dx in -width..=width {
+                units.extend(self.units_at(Position::new(center.x + dx, center.y + dy)));
+            }
+        }
+        units.sort_by_key(|unit| (center.distance_to(&unit.pos) as Coordinate, unit.id.clone()));
+        units
+    }
+
+    /// Returns unit nearest to `position`, optionally of given team and type
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    /// - `position` - [`Position`] to measure from
+    /// - `team` - team id of unit or `None` for any team
+    /// - `unit_type` - [`UnitType`] of unit or `None` for any type
+    ///
+    /// # Returns
+    ///
+    /// Nearest unit or `None` if there is no matching unit. Ties are broken by
+    /// unit id
+    pub fn nearest(
+        &self, position: Position, team: Option<TeamId>, unit_type: Option<UnitType>,
+    ) -> Option<&Unit> {
+        self.cells
+            .values()
+            .flatten()
+            .filter(|unit| team.is_none_or(|team| unit.team == team))
+            .filter(|unit| unit_type.is_none_or(|unit_type| unit.unit_type == unit_type))
+            .min_by_key(|unit| (position.distance_to(&unit.pos) as Coordinate, &unit.id))
+    }
+
+    /// Returns count of indexed units
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// Units count
+    pub fn len(&self) -> usize { self.cells.values().map(|units| units.len()).sum() }
+
+    /// Whether or not index has no units
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    ///
+    /// # Returns
+    ///
+    /// `bool` value
+    pub fn is_empty(&self) -> bool { self.cells.is_empty() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn players(units: &[(TeamId, UnitType, &str, i32, i32)]) -> Vec<Player> {
+        let mut players = vec![Player::new(0), Player::new(1)];
+        for &(team, unit_type, id, x, y) in units {
+            let unit = Unit::new(team, unit_type, id.to_string(), Position::new(x, y), 0.0);
+            players[team as usize].units.push(unit);
+        }
+        players
+    }
+
+    fn ids(units: &[&Unit]) -> Vec<String> { units.iter().map(|unit| unit.id.clone()).collect() }
+
+    #[test]
+    fn units_are_indexed_by_position() {
+        let index = UnitIndex::from_players(&players(&[
+            (0, UnitType::Worker, "u_1", 2, 2),
+            (0, UnitType::Cart, "u_2", 2, 2),
+            (1, UnitType::Worker, "u_3", 5, 1),
+        ]));
+
+        assert_eq!(index.len(), 3);
+        assert_eq!(index.units_at(Position::new(2, 2)).len(), 2);
+        assert_eq!(index.units_at(Position::new(5, 1))[0].id, "u_3");
+        assert!(index.is_occupied(Position::new(5, 1)));
+        assert!(!index.is_occupied(Position::new(1, 1)));
+        assert!(index.units_at(Position::new(-1, 0)).is_empty());
+    }
+
+    #[test]
+    fn units_in_radius_are_ordered_by_distance() {
+        let index = UnitIndex::from_players(&players(&[
+            (0, UnitType::Worker, "u_1", 3, 3),
+            (1, UnitType::Worker, "u_2", 4, 3),
+            (0, UnitType::Worker, "u_3", 3, 5),
+            (1, UnitType::Worker, "u_4", 4, 4),
+            (0, UnitType::Worker, "u_5", 6, 6),
+        ]));
+
+        let center = Position::new(3, 4);
+        assert_eq!(ids(&index.units_in_radius(center, 0)), Vec::<String>::new());
+        assert_eq!(
+            ids(&index.units_in_radius(center, 1)),
+            ["u_1", "u_3", "u_4"]
+        );
+        assert_eq!(
+            ids(&index.units_in_radius(center, 2)),
+            ["u_1", "u_3", "u_4", "u_2"]
+        );
+    }
+
+    #[test]
+    fn nearest_unit_is_filtered_by_team_and_type() {
+        let index = UnitIndex::from_players(&players(&[
+            (0, UnitType::Worker, "u_1", 0, 0),
+            (1, UnitType::Worker, "u_2", 3, 3),
+            (0, UnitType::Cart, "u_3", 2, 3),
+            (0, UnitType::Worker, "u_4", 3, 0),
+        ]));
+        let position = Position::new(3, 2);
+        let nearest = |team, unit_type| {
+            index
+                .nearest(position, team, unit_type)
+                .map(|unit| unit.id.as_str())
+        };
+
+        assert_eq!(nearest(None, None), Some("u_2"));
+        assert_eq!(nearest(Some(0), None), Some("u_3"));
+        assert_eq!(nearest(Some(0), Some(UnitType::Worker)), Some("u_4"));
+        assert_eq!(nearest(Some(1), Some(UnitType::Cart)), None);
+    }
+
+    #[test]
+    fn update_replaces_indexed_units() {
+        let mut index = UnitIndex::from_players(&players(&[(0, UnitType::Worker, "u_1", 1, 1)]));
+
+        index.update(&players(&[(0, UnitType::Worker, "u_1", 1, 2)]));
+        assert!(!index.is_occupied(Position::new(1, 1)));
+        assert!(index.is_occupied(Position::new(1, 2)));
+
+        index.update(&players(&[]));
+        assert!(index.is_empty());
+        assert_eq!(index.len(), 0);
+    }
+}
@@ -0,0 +1,540 @@
+use std::{collections::HashSet, fmt};
+
+use crate::*;
+
+/// Represents reason why referee would drop [`Action`]
+#[derive(thiserror::Error, Clone, PartialEq, fmt::Debug)]
+pub enum ActionRejection {
+    /// Unit not exists or belongs to other team
+    #[error("Unknown unit: {0}")]
+    UnknownUnit(EntityId),
+
+    /// City tile not exists at position or belongs to other team
+    #[error("Unknown city tile at {0}")]
+    UnknownCityTile(Position),
+
+    /// Unit already has action this turn
+    #[error("Unit {0} already has action this turn")]
+    DuplicateUnitAction(EntityId),
+
+    /// City tile already has action this turn
+    #[error("City tile at {0} already has action this turn")]
+    DuplicateCityTileAction(Position),
+
+    /// Unit cooldown is not less than 1, see [`Unit::can_act`]
+    #[error("Unit {unit_id} is on cooldown {cooldown}")]
+    UnitOnCooldown {
+        /// Id of [`Unit`]
+        unit_id:  EntityId,
+        /// Current cooldown of [`Unit`]
+        cooldown: Cooldown,
+    },
+
+    /// City tile cooldown is not less than 1, see [`CityTile::can_act`]
+    #[error("City tile at {position} is on cooldown {cooldown}")]
+    CityTileOnCooldown {
+        /// [`Position`] of [`CityTile`]
+        position: Position,
+        /// Current cooldown of [`CityTile`]
+        cooldown: Cooldown,
+    },
+
+    /// Move target is outside of [`GameMap`]
+    #[error("Unit {unit_id} moves off the map to {position}")]
+    MoveOffMap {
+        /// Id of [`Unit`]
+        unit_id:  EntityId,
+        /// Target [`Position`] of move
+        position: Position,
+    },
+
+    /// Move target is city tile of other team
+    #[error("Unit {unit_id} moves into enemy city tile at {position}")]
+    MoveIntoEnemyCity {
+        /// Id of [`Unit`]
+        unit_id:  EntityId,
+        /// Target [`Position`] of move
+        position: Position,
+    },
+
+    /// Only workers can build cities and pillage roads
+    #[error("Unit {0} is not a worker")]
+    NotWorker(EntityId),
+
+    /// City can not be built on cell with resource
+    #[error("Cannot build city on resource cell at {0}")]
+    BuildCityOnResource(Position),
+
+    /// City can not be built on existing city tile
+    #[error("Cannot build city on city tile at {0}")]
+    BuildCityOnCityTile(Position),
+
+    /// Unit has not enough resources in cargo. Transfer is rejected only if
+    /// unit has none of resource, larger amount is clamped
+    #[error("Unit {unit_id} has {available} resources, {required} required")]
+    NotEnoughResources {
+        /// Id of [`Unit`]
+        unit_id:   EntityId,
+        /// Amount of resources required
+        required:  ResourceAmount,
+        /// Amount of resources in cargo
+        available: ResourceAmount,
+    },
+
+    /// Transfer destination is not adjacent to source unit
+    #[error("Unit {unit_id} is not adjacent to {destination_id}")]
+    TransferNotAdjacent {
+        /// Id of [`Unit`] transfering resource
+        unit_id:        EntityId,
+        /// Id of [`Unit`] receiving resource
+        destination_id: EntityId,
+    },
+
+    /// Destination unit has no cargo space left, so nothing is transfered.
+    /// Transfer larger than space left is not rejected, amount is clamped
+    #[error("Unit {destination_id} has {space_left} cargo space left, {amount} transfered")]
+    TransferOverCapacity {
+        /// Id of [`Unit`] receiving resource
+        destination_id: EntityId,
+        /// Cargo space left of [`Unit`] receiving resource
+        space_left:     ResourceAmount,
+        /// Amount of resource transfered
+        amount:         ResourceAmount,
+    },
+
+    /// Road can not be pillaged, because there is no road or city tile is on
+    /// the cell
+    #[error("Nothing to pillage at {0}")]
+    NothingToPillage(Position),
+
+    /// Team has as many units as city tiles
+    #[error("Unit limit reached: {units} units for {city_tiles} city tiles")]
+    UnitLimitReached {
+        /// Count of team units including built this turn
+        units:      usize,
+        /// Count of team city tiles
+        city_tiles: usize,
+    },
+}
+
+/// Checks [`Actions`][Action] of one team against current state before they
+/// are flushed, so actions referee would drop silently are caught by bot
+///
+/// Validator is stateful: it remembers units and city tiles which already got
+/// action and units built during the turn
+///
+/// # Examples
+///
+/// ```
+/// # use lux_ai_api::*;
+/// # let players = vec![Player::new(0), Player::new(1)];
+/// # let mut simulator = Simulator::new(1, GameMap::new(12, 12), players);
+/// # let (pos, id) = (Position::new(1, 1), "u_1".to_string());
+/// # simulator.players[0].units.push(Unit::new(0, UnitType::Worker, id, pos, 0.0));
+/// # let agent = simulator.to_agent(0);
+/// # let unit = &agent.player().units[0];
+/// let mut validator = ActionValidator::from_agent(&agent);
+/// if let Err(rejection) = validator.validate(&unit.build_city()) {
+///     eprintln!("{}", rejection);
+/// }
+/// # Ok::<(), LuxAiError>(())
+/// ```
+///
+/// # See also
+///
+/// Check <https://www.lux-ai.org/specs-2021#Units>
+/// Check <https://www.lux-ai.org/specs-2021#CityTiles>
+pub struct ActionValidator<'a> {
+    game_map:         &'a GameMap,
+    players:          &'a [Player],
+    team:             TeamId,
+    acted_units:      HashSet<EntityId>,
+    acted_city_tiles: HashSet<Position>,
+    units_built:      usize,
+}
+
+impl<'a> ActionValidator<'a> {
+    /// Creates [`ActionValidator`] for actions of given team
+    ///
+    /// # Parameters
+    ///
+    /// - `game_map` - [`GameMap`] of current turn
+    /// - `players` - all [`players`][Player] participating in match
+    /// - `team` - team id of validated actions
+    ///
+    /// # Returns
+    ///
+    /// A new created [`ActionValidator`]
+    pub fn new(game_map: &'a GameMap, players: &'a [Player], team: TeamId) -> Self {
+        Self {
+            game_map,
+            players,
+            team,
+            acted_units: HashSet::new(),
+            acted_city_tiles: HashSet::new(),
+            units_built: 0,
+        }
+    }
+
+    /// Creates [`ActionValidator`] for actions of [`Agent`]'s team
+    ///
+    /// # Parameters
+    ///
+    /// - `agent` - [`Agent`] reference
+    ///
+    /// # Returns
+    ///
+    /// A new created [`ActionValidator`]
+    pub fn from_agent(agent: &'a Agent) -> Self {
+        Self::new(&agent.game_map, &agent.players, agent.team)
+    }
+
+    /// Validates `action` and remembers its entity as acted, if `action` is
+    /// valid
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - mutable Self reference
+    /// - `action` - [`Action`] to validate
+    ///
+    /// # Returns
+    ///
+    /// Nothing or [`ActionRejection`] with reason why referee would drop
+    /// `action`
+    pub fn validate(&mut self, action: &Action) -> Result<(), ActionRejection> {
+        if let Some(unit_id) = action.unit_id() {
+            self.validate_unit_action(unit_id, action)?;
+            self.acted_units.insert(unit_id.clone());
+        } else if let Some(position) = action.city_tile_position() {
+            self.validate_city_tile_action(position, action)?;
+            self.acted_city_tiles.insert(position);
+            if !matches!(action, ActionKind::Research { .. }) {
+                self.units_built += 1;
+            }
+        }
+        Ok(())
+    }
+
+    fn player(&self) -> &Player { &self.players[self.team as usize] }
+
+    fn find_unit(&self, unit_id: &EntityId) -> Result<&Unit, ActionRejection> {
+        self.player()
+            .units
+            .iter()
+            .find(|unit| &unit.id == unit_id)
+            .ok_or_else(|| ActionRejection::UnknownUnit(unit_id.clone()))
+    }
+
+    fn validate_unit_action(
+        &self, unit_id: &EntityId, action: &Action,
+    ) -> Result<(), ActionRejection> {
+        let unit = self.find_unit(unit_id)?;
+        if self.acted_units.contains(unit_id) {
+            return Err(ActionRejection::DuplicateUnitAction(unit_id.clone()));
+        }
+        if !unit.can_act() {
+            return Err(ActionRejection::UnitOnCooldown {
+                unit_id:  unit_id.clone(),
+                cooldown: unit.cooldown,
+            });
+        }
+
+        match action {
+            ActionKind::Move { direction, .. } => {
+                let position = match self.game_map.translate(unit.pos, *direction, 1) {
+                    Some(position) => position,
+                    None =>
+                        return Err(ActionRejection::MoveOffMap {
+                            unit_id:  unit_id.clone(),
+                            position: unit.pos.offset(*direction, 1),
+                        }),
+                };
+                let enemy_city = self
+                    .game_map
+                    .city_tile_at(position)
+                    .is_some_and(|city_tile| city_tile.teamid != self.team);
+                if enemy_city {
+                    return Err(ActionRejection::MoveIntoEnemyCity {
+                        unit_id: unit_id.clone(),
+                        position,
+                    });
+                }
+            },
+            ActionKind::Transfer {
+                destination_id,
+                resource_type,
+                amount,
+                ..
+            } => {
+                let destination = self.find_unit(destination_id)?;
+                if !unit.pos.is_adjacent(&destination.pos) || unit.id == destination.id {
+                    return Err(ActionRejection::TransferNotAdjacent {
+                        unit_id:        unit_id.clone(),
+                        destination_id: destination_id.clone(),
+                    });
+                }
+                if *amount <= 0 || unit.cargo[*resource_type] == 0 {
+                    return Err(ActionRejection::NotEnoughResources {
+                        unit_id:   unit_id.clone(),
+                        required:  *amount,
+                        available: unit.cargo[*resource_type],
+                    });
+                }
+                let space_left = destination.get_cargo_space_left();
+                if space_left == 0 {
+                    return Err(ActionRejection::TransferOverCapacity {
+                        destination_id: destination_id.clone(),
+                        space_left,
+                        amount: *amount,
+                    });
+                }
+            },
+            ActionKind::BuildCity { .. } => {
+                let cell = &self.game_map[unit.pos];
+                if unit.unit_type != UnitType::Worker {
+                    return Err(ActionRejection::NotWorker(unit_id.clone()));
+                }
+                if cell.has_resource() {
+                    return Err(ActionRejection::BuildCityOnResource(unit.pos));
+                }
+                if cell.citytile.is_some() {
+                    return Err(ActionRejection::BuildCityOnCityTile(unit.pos));
+                }
+                if unit.cargo_space_used() < City::city_build_cost() {
+                    return Err(ActionRejection::NotEnoughResources {
+                        unit_id:   unit_id.clone(),
+                        required:  City::city_build_cost(),
+                        available: unit.cargo_space_used(),
+                    });
+                }
+            },
+            ActionKind::Pillage { .. } => {
+                if unit.unit_type != UnitType::Worker {
+                    return Err(ActionRejection::NotWorker(unit_id.clone()));
+                }
+                let cell = &self.game_map[unit.pos];
+                if cell.road <= 0.0 || cell.citytile.is_some() {
+                    return Err(ActionRejection::NothingToPillage(unit.pos));
+                }
+            },
+            _ => {},
+        }
+        Ok(())
+    }
+
+    fn validate_city_tile_action(
+        &self, position: Position, action: &Action,
+    ) -> Result<(), ActionRejection> {
+        if !self.game_map.contains(position) {
+            return Err(ActionRejection::UnknownCityTile(position));
+        }
+        let city_tile = match self.game_map.city_tile_at(position) {
+            Some(city_tile) if city_tile.teamid == self.team => city_tile,
+            _ => return Err(ActionRejection::UnknownCityTile(position)),
+        };
+        if self.acted_city_tiles.contains(&position) {
+            return Err(ActionRejection::DuplicateCityTileAction(position));
+        }
+        if !city_tile.can_act() {
+            return Err(ActionRejection::CityTileOnCooldown {
+                position,
+                cooldown: city_tile.cooldown,
+            });
+        }
+
+        if let ActionKind::BuildWorker { .. } | ActionKind::BuildCart { .. } = action {
+            let units = self.player().units.len() + self.units_built;
+            let city_tiles = self
+                .player()
+                .cities
+                .values()
+                .map(|city| city.citytiles.len())
+                .sum();
+            if units >= city_tiles {
+                return Err(ActionRejection::UnitLimitReached { units, city_tiles });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state() -> (GameMap, Vec<Player>) {
+        (GameMap::new(8, 8), vec![Player::new(0), Player::new(1)])
+    }
+
+    fn add_unit(
+        players: &mut [Player], team: TeamId, unit_type: UnitType, id: &str, x: i32, y: i32,
+    ) -> Unit {
+        let unit = Unit::new(team, unit_type, id.to_string(), Position::new(x, y), 0.0);
+        players[team as usize].units.push(unit.clone());
+        unit
+    }
+
+    fn add_city(
+        game_map: &mut GameMap, players: &mut [Player], team: TeamId, id: &str, x: i32, y: i32,
+    ) -> CityTile {
+        let mut city = City::new(team, id.to_string(), 0.0, 0.0);
+        city.add_city_tile(game_map, Position::new(x, y), 0.0);
+        players[team as usize]
+            .cities
+            .insert(city.cityid.clone(), city);
+        CityTile::new(team, id.to_string(), Position::new(x, y), 0.0)
+    }
+
+    #[test]
+    fn unit_on_cooldown_is_rejected() {
+        let (game_map, mut players) = state();
+        let mut unit = add_unit(&mut players, 0, UnitType::Worker, "u_1", 1, 1);
+        unit.cooldown = 2.0;
+        players[0].units[0] = unit.clone();
+
+        let mut validator = ActionValidator::new(&game_map, &players, 0);
+
+        assert_eq!(
+            validator.validate(&unit.move_(Direction::East)),
+            Err(ActionRejection::UnitOnCooldown {
+                unit_id:  unit.id.clone(),
+                cooldown: 2.0,
+            })
+        );
+    }
+
+    #[test]
+    fn move_off_map_is_rejected() {
+        let (game_map, mut players) = state();
+        let unit = add_unit(&mut players, 0, UnitType::Worker, "u_1", 0, 0);
+
+        let mut validator = ActionValidator::new(&game_map, &players, 0);
+
+        assert_eq!(
+            validator.validate(&unit.move_(Direction::West)),
+            Err(ActionRejection::MoveOffMap {
+                unit_id:  unit.id.clone(),
+                position: Position::new(-1, 0),
+            })
+        );
+    }
+
+    #[test]
+    fn move_into_enemy_city_is_rejected() {
+        let (mut game_map, mut players) = state();
+        let unit = add_unit(&mut players, 0, UnitType::Worker, "u_1", 1, 1);
+        add_city(&mut game_map, &mut players, 1, "c_1", 2, 1);
+
+        let mut validator = ActionValidator::new(&game_map, &players, 0);
+
+        assert_eq!(
+            validator.validate(&unit.move_(Direction::East)),
+            Err(ActionRejection::MoveIntoEnemyCity {
+                unit_id:  unit.id.clone(),
+                position: Position::new(2, 1),
+            })
+        );
+    }
+
+    #[test]
+    fn build_city_on_resource_is_rejected() {
+        let (mut game_map, mut players) = state();
+        game_map[Position::new(1, 1)].resource = Some(Resource::new(ResourceType::Wood, 100));
+        let mut unit = add_unit(&mut players, 0, UnitType::Worker, "u_1", 1, 1);
+        unit.cargo.wood = City::city_build_cost();
+        players[0].units[0] = unit.clone();
+
+        let mut validator = ActionValidator::new(&game_map, &players, 0);
+
+        assert_eq!(
+            validator.validate(&unit.build_city()),
+            Err(ActionRejection::BuildCityOnResource(Position::new(1, 1)))
+        );
+    }
+
+    #[test]
+    fn unit_limit_is_rejected() {
+        let (mut game_map, mut players) = state();
+        add_unit(&mut players, 0, UnitType::Worker, "u_1", 1, 1);
+        let city_tile = add_city(&mut game_map, &mut players, 0, "c_1", 3, 3);
+
+        let mut validator = ActionValidator::new(&game_map, &players, 0);
+
+        assert_eq!(
+            validator.validate(&city_tile.build_worker()),
+            Err(ActionRejection::UnitLimitReached {
+                units:      1,
+                city_tiles: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn duplicate_unit_action_is_rejected() {
+        let (game_map, mut players) = state();
+        let unit = add_unit(&mut players, 0, UnitType::Worker, "u_1", 1, 1);
+
+        let mut validator = ActionValidator::new(&game_map, &players, 0);
+
+        assert_eq!(validator.validate(&unit.move_(Direction::East)), Ok(()));
+        assert_eq!(
+            validator.validate(&unit.move_(Direction::South)),
+            Err(ActionRejection::DuplicateUnitAction(unit.id.clone()))
+        );
+    }
+
+    #[test]
+    fn transfer_to_full_unit_is_rejected() {
+        let (game_map, mut players) = state();
+        let mut unit = add_unit(&mut players, 0, UnitType::Worker, "u_1", 1, 1);
+        let mut destination = add_unit(&mut players, 0, UnitType::Worker, "u_2", 2, 1);
+        unit.cargo.wood = 10;
+        destination.cargo.coal = destination.get_cargo_space_left();
+        players[0].units = vec![unit.clone(), destination.clone()];
+
+        let mut validator = ActionValidator::new(&game_map, &players, 0);
+
+        assert_eq!(
+            validator.validate(&unit.transfer(&destination, ResourceType::Wood, 10)),
+            Err(ActionRejection::TransferOverCapacity {
+                destination_id: destination.id.clone(),
+                space_left:     0,
+                amount:         10,
+            })
+        );
+    }
+
+    #[test]
+    fn valid_actions_are_accepted() {
+        let (mut game_map, mut players) = state();
+        let mut builder = add_unit(&mut players, 0, UnitType::Worker, "u_1", 1, 1);
+        let mover = add_unit(&mut players, 0, UnitType::Worker, "u_2", 5, 5);
+        let mut carrier = add_unit(&mut players, 0, UnitType::Cart, "u_3", 2, 3);
+        let receiver = add_unit(&mut players, 0, UnitType::Worker, "u_4", 2, 4);
+        builder.cargo.wood = City::city_build_cost();
+        carrier.cargo.coal = 10;
+        players[0].units[0] = builder.clone();
+        players[0].units[2] = carrier.clone();
+        for x in 0..4 {
+            add_city(
+                &mut game_map,
+                &mut players,
+                0,
+                &format!("c_{}", x + 1),
+                x,
+                6,
+            );
+        }
+        let city_tile = add_city(&mut game_map, &mut players, 0, "c_5", 6, 6);
+
+        let mut validator = ActionValidator::new(&game_map, &players, 0);
+
+        assert_eq!(validator.validate(&builder.build_city()), Ok(()));
+        assert_eq!(validator.validate(&mover.move_(Direction::North)), Ok(()));
+        assert_eq!(
+            validator.validate(&carrier.transfer(&receiver, ResourceType::Coal, 20)),
+            Ok(())
+        );
+        assert_eq!(validator.validate(&city_tile.build_worker()), Ok(()));
+    }
+}
@@ -0,0 +1,415 @@
+use std::{collections::HashMap, fmt};
+
+use crate::*;
+
+/// Unit tracked across turns by its [`EntityId`]
+#[derive(Clone, fmt::Debug)]
+pub struct UnitRecord {
+    /// Turn when unit was seen first time
+    pub first_seen: TurnAmount,
+
+    /// Turn when unit was seen last time
+    pub last_seen: TurnAmount,
+
+    /// Last known state of unit
+    pub unit: Unit,
+}
+
+/// City tracked across turns by its [`EntityId`]
+#[derive(Clone, fmt::Debug)]
+pub struct CityRecord {
+    /// Turn when city was seen first time
+    pub first_seen: TurnAmount,
+
+    /// Turn when city was seen last time
+    pub last_seen: TurnAmount,
+
+    /// Last known state of city, its [`CityTileIds`][CityTileId] are valid
+    /// only for [`GameMap`] of `last_seen` turn
+    pub city: City,
+
+    /// Last known state of city tiles of city
+    pub citytiles: Vec<CityTile>,
+}
+
+/// Change of road development progress on [`Cell`]
+#[derive(Clone, PartialEq, fmt::Debug)]
+pub struct RoadChange {
+    /// [`Position`] of changed [`Cell`]
+    pub position: Position,
+
+    /// Road development progress on previous turn
+    pub previous: RoadAmount,
+
+    /// Road development progress on current turn
+    pub current: RoadAmount,
+}
+
+/// Resource and road of [`Cell`] on previous turn, the only cell state
+/// [`TurnDiff`] needs
+#[derive(Clone, fmt::Debug)]
+struct CellRecord {
+    resource: Option<Resource>,
+    road:     RoadAmount,
+}
+
+impl CellRecord {
+    fn new(cell: &Cell) -> Self {
+        Self {
+            resource: cell.resource.clone().filter(|_| cell.has_resource()),
+            road:     cell.road,
+        }
+    }
+}
+
+/// Changes between two consecutive turns
+///
+/// # See also
+///
+/// Check <https://www.lux-ai.org/specs-2021#Environment>
+#[derive(Clone, Default, fmt::Debug)]
+pub struct TurnDiff {
+    /// Turn index of current state
+    pub turn: TurnAmount,
+
+    /// Units appeared on current turn
+    pub units_spawned: Vec<Unit>,
+
+    /// Units disappeared on current turn, in their last known state
+    pub units_died: Vec<Unit>,
+
+    /// Cities founded on current turn
+    pub cities_founded: Vec<City>,
+
+    /// Cities merged into other city on current turn, as pairs of merged city
+    /// id and id of city it was merged into
+    pub cities_merged: Vec<(EntityId, EntityId)>,
+
+    /// Cities ran out of fuel at night, in their last known state
+    ///
+    /// # See also
+    ///
+    /// Check <https://www.lux-ai.org/specs-2021#Day/Night%20Cycle>
+    pub cities_lost: Vec<CityRecord>,
+
+    /// Resources depleted on current turn, in their last known state
+    pub resources_depleted: Vec<(Position, Resource)>,
+
+    /// Road development changes on current turn
+    pub road_changes: Vec<RoadChange>,
+}
+
+/// Persistent model of the world, that matches units and cities by
+/// [`EntityId`] across turns and exposes changes of last turn
+///
+/// # Examples
+///
+/// ```
+/// # use std::io;
+/// # use lux_ai_api::*;
+/// # let input = "0\n12 12\nD_DONE\n";
+/// # let mut environment = Environment::with_io(io::Cursor::new(input), Vec::new());
+/// # let mut agent = Agent::new(&mut environment)?;
+/// agent.update_turn(&mut environment)?;
+/// for unit in agent.world.diff.units_died.iter() {
+///     eprintln!("Lost unit {} at {}", unit.id, unit.pos);
+/// }
+/// # Ok::<(), LuxAiError>(())
+/// ```
+#[derive(Clone, Default, fmt::Debug)]
+pub struct WorldModel {
+    /// Alive units by id
+    pub units: HashMap<EntityId, UnitRecord>,
+
+    /// Existing cities by id
+    pub cities: HashMap<EntityId, CityRecord>,
+
+    /// Changes between previous and current turn
+    pub diff: TurnDiff,
+
+    /// Number of highest [`Unit`] id ever seen, referee never reuses ids of
+    /// dead units
+    pub max_unit_id: u32,
+
+    /// Number of highest [`City`] id ever seen, referee never reuses ids of
+    /// lost cities
+    pub max_city_id: u32,
+
+    previous_cells: Vec<CellRecord>,
+}
+
+impl WorldModel {
+    /// Creates empty [`WorldModel`]
+    ///
+    /// # Parameters
+    ///
+    /// None
+    ///
+    /// # Returns
+    ///
+    /// A new created [`WorldModel`] without known units and cities
+    pub fn new() -> Self { Self::default() }
+
+    /// Updates model with state of new turn and computes [`TurnDiff`]
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - mutable Self reference
+    /// - `turn` - turn index of new state
+    /// - `game_map` - [`GameMap`] of new turn
+    /// - `players` - all [`players`][Player] of new turn
+    ///
+    /// # Returns
+    ///
+    /// Reference to computed [`TurnDiff`]
+    pub fn update(
+        &mut self, turn: TurnAmount, game_map: &GameMap, players: &[Player],
+    ) -> &TurnDiff {
+        let mut diff = TurnDiff {
+            turn,
+            ..TurnDiff::default()
+        };
+
+        self.update_units(turn, players, &mut diff);
+        self.update_cities(turn, game_map, players, &mut diff);
+        self.update_cells(game_map, &mut diff);
+        self.diff = diff;
+        &self.diff
+    }
+
+    /// Returns how many turns unit is alive
+    ///
+    /// # Parameters
+    ///
+    /// - `self` - Self reference
+    /// - `unit_id` - id of [`Unit`]
+    ///
+    /// # Returns
+    ///
+    /// Turns amount or `None` if unit is not alive
+    pub fn unit_age(&self, unit_id: &str) -> Option<TurnAmount> {
+        self.units
+            .get(unit_id)
+            .map(|record| record.last_seen - record.first_seen)
+    }
+
+    fn update_units(&mut self, turn: TurnAmount, players: &[Player], diff: &mut TurnDiff) {
+        let mut units = HashMap::new();
+        for unit in players.iter().flat_map(|player| player.units.iter()) {
+            if let Some(id) = parse_entity_id(&unit.id, UNIT_ID_PREFIX) {
+                self.max_unit_id = self.max_unit_id.max(id);
+            }
+            let first_seen = match self.units.remove(&unit.id) {
+                Some(record) => record.first_seen,
+                None => {
+                    diff.units_spawned.push(unit.clone());
+                    turn
+                },
+            };
+            let record = UnitRecord {
+                first_seen,
+                last_seen: turn,
+                unit: unit.clone(),
+            };
+            units.insert(unit.id.clone(), record);
+        }
+
+        diff.units_died = self.units.drain().map(|(_, record)| record.unit).collect();
+        self.units = units;
+    }
+
+    fn update_cities(
+        &mut self, turn: TurnAmount, game_map: &GameMap, players: &[Player], diff: &mut TurnDiff,
+    ) {
+        let mut cities = HashMap::new();
+        for city in players.iter().flat_map(|player| player.cities.values()) {
+            if let Some(id) = parse_entity_id(&city.cityid, CITY_ID_PREFIX) {
+                self.max_city_id = self.max_city_id.max(id);
+            }
+            let first_seen = match self.cities.remove(&city.cityid) {
+                Some(record) => record.first_seen,
+                None => {
+                    diff.cities_founded.push(city.clone());
+                    turn
+                },
+            };
+            let record = CityRecord {
+                first_seen,
+                last_seen: turn,
+                city: city.clone(),
+                citytiles: city.city_tiles(game_map).cloned().collect(),
+            };
+            cities.insert(city.cityid.clone(), record);
+        }
+
+        for (city_id, record) in self.cities.drain() {
+            let merged_into = record
+                .citytiles
+                .iter()
+                .map(|city_tile| city_tile.pos)
+                .find_map(|position| {
+                    cities.values().find(|other| {
+                        other.city.teamid == record.city.teamid &&
+                            other
+                                .citytiles
+                                .iter()
+                                .any(|city_tile| city_tile.pos == position)
+                    })
+                });
+            match merged_into {
+                Some(other) => diff
+                    .cities_merged
+                    .push((city_id, other.city.cityid.clone())),
+                None => diff.cities_lost.push(record),
+            }
+        }
+        self.cities = cities;
+    }
+
+    fn update_cells(&mut self, game_map: &GameMap, diff: &mut TurnDiff) {
+        let cells = game_map.map.iter().flat_map(|row| row.iter());
+        for (previous, current) in self.previous_cells.iter().zip(cells.clone()) {
+            if let Some(resource) = previous.resource.as_ref() {
+                if !current.has_resource() {
+                    diff.resources_depleted
+                        .push((current.pos, resource.clone()));
+                }
+            }
+            if previous.road != current.road {
+                diff.road_changes.push(RoadChange {
+                    position: current.pos,
+                    previous: previous.road,
+                    current:  current.road,
+                });
+            }
+        }
+        self.previous_cells = cells.map(CellRecord::new).collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn players() -> Vec<Player> { vec![Player::new(0), Player::new(1)] }
+
+    fn add_unit(players: &mut [Player], team: TeamId, id: &str, x: i32, y: i32) {
+        let unit = Unit::new(
+            team,
+            UnitType::Worker,
+            id.to_string(),
+            Position::new(x, y),
+            0.0,
+        );
+        players[team as usize].units.push(unit);
+    }
+
+    fn add_city(
+        game_map: &mut GameMap, players: &mut [Player], team: TeamId, id: &str,
+        positions: &[(i32, i32)],
+    ) {
+        let mut city = City::new(team, id.to_string(), 0.0, 0.0);
+        for &(x, y) in positions {
+            city.add_city_tile(game_map, Position::new(x, y), 0.0);
+        }
+        players[team as usize]
+            .cities
+            .insert(city.cityid.clone(), city);
+    }
+
+    fn ids<'a>(ids: impl Iterator<Item = &'a EntityId>) -> Vec<&'a str> {
+        let mut ids: Vec<_> = ids.map(|id| id.as_str()).collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn units_are_matched_by_id_across_turns() {
+        let game_map = GameMap::new(8, 8);
+        let mut world = WorldModel::new();
+
+        let mut players = players();
+        add_unit(&mut players, 0, "u_1", 1, 1);
+        add_unit(&mut players, 1, "u_2", 6, 6);
+        world.update(1, &game_map, &players);
+
+        let mut players = self::players();
+        add_unit(&mut players, 0, "u_1", 2, 1);
+        add_unit(&mut players, 0, "u_3", 4, 4);
+        let diff = world.update(2, &game_map, &players);
+
+        assert_eq!(ids(diff.units_spawned.iter().map(|unit| &unit.id)), ["u_3"]);
+        assert_eq!(ids(diff.units_died.iter().map(|unit| &unit.id)), ["u_2"]);
+        assert_eq!(diff.units_died[0].pos, Position::new(6, 6));
+        assert_eq!(world.units["u_1"].first_seen, 1);
+        assert_eq!(world.units["u_1"].unit.pos, Position::new(2, 1));
+        assert_eq!(world.unit_age("u_1"), Some(1));
+        assert_eq!(world.unit_age("u_2"), None);
+        assert_eq!(world.max_unit_id, 3);
+    }
+
+    #[test]
+    fn cities_are_founded_merged_and_lost() {
+        let mut world = WorldModel::new();
+
+        let (mut game_map, mut players) = (GameMap::new(8, 8), players());
+        add_city(&mut game_map, &mut players, 0, "c_1", &[(1, 1)]);
+        add_city(&mut game_map, &mut players, 0, "c_2", &[(3, 1)]);
+        let diff = world.update(1, &game_map, &players);
+        assert_eq!(
+            ids(diff.cities_founded.iter().map(|city| &city.cityid)),
+            ["c_1", "c_2"]
+        );
+
+        let (mut game_map, mut players) = (GameMap::new(8, 8), self::players());
+        add_city(
+            &mut game_map,
+            &mut players,
+            0,
+            "c_1",
+            &[(1, 1), (2, 1), (3, 1)],
+        );
+        let diff = world.update(2, &game_map, &players);
+        assert!(diff.cities_founded.is_empty());
+        assert_eq!(diff.cities_merged, [("c_2".to_string(), "c_1".to_string())]);
+        assert!(diff.cities_lost.is_empty());
+
+        let diff = world.update(3, &GameMap::new(8, 8), &self::players());
+        assert!(diff.cities_merged.is_empty());
+        assert_eq!(
+            ids(diff.cities_lost.iter().map(|record| &record.city.cityid)),
+            ["c_1"]
+        );
+        assert!(world.cities.is_empty());
+        assert_eq!(world.max_city_id, 2);
+    }
+
+    #[test]
+    fn depleted_resources_and_road_changes_are_reported() {
+        let mut game_map = GameMap::new(8, 8);
+        let mut world = WorldModel::new();
+        game_map[Position::new(0, 0)].resource = Some(Resource::new(ResourceType::Wood, 100));
+        game_map[Position::new(5, 5)].resource = Some(Resource::new(ResourceType::Coal, 50));
+        let diff = world.update(1, &game_map, &players());
+        assert!(diff.resources_depleted.is_empty());
+        assert!(diff.road_changes.is_empty());
+
+        game_map[Position::new(0, 0)].resource = None;
+        game_map[Position::new(5, 5)].resource = Some(Resource::new(ResourceType::Coal, 30));
+        game_map[Position::new(4, 4)].road = 1.5;
+        let diff = world.update(2, &game_map, &players());
+
+        assert_eq!(
+            diff.resources_depleted,
+            [(Position::new(0, 0), Resource::new(ResourceType::Wood, 100))]
+        );
+        assert_eq!(
+            diff.road_changes,
+            [RoadChange {
+                position: Position::new(4, 4),
+                previous: 0.0,
+                current:  1.5,
+            }]
+        );
+    }
+}
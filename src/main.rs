@@ -1,7 +1,9 @@
-use std::cell::Ref;
+use lux_ai::{Action, ActionKind, Agent, Cell, City, CityTile, Commands, Direction, Direction::*,
+             Environment, LuxAiResult, MoveCoordinator, PathFinder, Position, Resource,
+             ResourceType::*, TurnAmount, Unit, UnitType::*};
 
-use lux_ai::{Action, Agent, Annotate, Cell, City, CityTile, Commands, Direction::*, Environment,
-             LuxAiError, LuxAiResult, Position, Resource, ResourceType::*, Unit, UnitType::*};
+/// Turn by which coal should be researched, if city tiles allow it
+const COAL_TARGET_TURN: TurnAmount = 200;
 
 struct Engine {
     environment:        Environment,
@@ -20,49 +22,59 @@ impl Engine {
         })
     }
 
-    fn is_day(&self) -> bool { self.agent.turn % 40 < 30 }
-
-    fn is_night(&self) -> bool { !self.is_day() }
-
-    fn turns_until_night(&self) -> Option<i32> {
-        if self.is_night() {
-            return None;
-        }
-
-        Some(30 - (self.agent.turn % 40))
-    }
-
     fn turn(&mut self) -> LuxAiResult<()> {
         self.agent.update_turn(&mut self.environment)?;
         self.update_eligible_resources();
 
         let player = self.agent.player().clone();
 
+        let mut moves = vec![];
         for unit in player.units.iter() {
-            match unit.unit_type {
-                Worker if unit.can_act() =>
-                    if let Some(action) = self.turn_worker(unit)? {
-                        self.environment.write_action(action);
-                    },
-                Cart if unit.can_act() =>
-                    if let Some(action) = self.turn_cart(unit)? {
-                        self.environment.write_action(action);
-                    },
-                _ => {},
+            let action = match unit.unit_type {
+                Worker if unit.can_act() => self.turn_worker(unit)?,
+                Cart if unit.can_act() => self.turn_cart(unit)?,
+                _ => None,
+            };
+            match action {
+                Some(ActionKind::Move { direction, .. }) => moves.push((unit, direction)),
+                Some(action) => self.environment.write_action(action),
+                None => {},
             }
         }
 
+        let mut coordinator = MoveCoordinator::from_agent(&self.agent);
+        for (unit, direction) in moves {
+            coordinator.request(unit, direction);
+        }
+        for action in coordinator.resolve().actions {
+            self.environment.write_action(action);
+        }
+
+        let mut research_tiles = player
+            .research_tiles_needed(
+                &self.agent.game_map,
+                self.agent.turn,
+                Coal,
+                COAL_TARGET_TURN,
+            )
+            .unwrap_or(0);
         for (_, city) in player.cities.into_iter() {
             for citytile in city.citytiles.iter() {
-                let citytile = citytile.borrow();
+                let citytile = self.agent.game_map[*citytile].clone();
                 if citytile.can_act() {
-                    if let Some(action) = self.turn_citytile(citytile)? {
+                    let research = research_tiles > 0;
+                    research_tiles = research_tiles.saturating_sub(1);
+                    if let Some(action) = self.turn_citytile(&citytile, research)? {
                         self.environment.write_action(action);
                     }
                 }
             }
         }
 
+        for (action, rejection) in self.environment.validate_actions(&self.agent) {
+            eprintln!("Dropped action `{}`: {}", action, rejection);
+        }
+
         self.environment.flush_actions()?;
         self.environment
             .write_raw_action(Commands::FINISH.to_string())?;
@@ -71,15 +83,14 @@ impl Engine {
         Ok(())
     }
 
-    fn closest_city_to(&self, pos: &Position) -> Option<Ref<CityTile>> {
+    fn closest_city_to(&self, pos: &Position) -> Option<&CityTile> {
         // Else if no cargo space left
         let mut closest_distance = f32::MAX;
-        let mut closest_city_tile: Option<Ref<CityTile>> = None;
+        let mut closest_city_tile: Option<&CityTile> = None;
 
         // Find nearest city tile
         for city in self.agent.player().cities.values() {
-            for city_tile in city.citytiles.iter() {
-                let city_tile = city_tile.borrow();
+            for city_tile in city.city_tiles(&self.agent.game_map) {
                 let distance = city_tile.pos.distance_to(pos);
 
                 if distance < closest_distance {
@@ -107,21 +118,13 @@ impl Engine {
         closest_resource_cell
     }
 
-    fn position_in_bounds(&self, pos: &Position) -> bool {
-        pos.x >= 0 &&
-            pos.y >= 0 &&
-            pos.x < self.agent.game_map.width &&
-            pos.y < self.agent.game_map.height
-    }
-
     fn empty_cell_adjacent_to(&self, pos: &Position) -> Option<&Cell> {
         let directions = vec![North, South, East, West];
         for direction in directions {
-            let pos = pos.translate(direction, 1);
-            if !self.position_in_bounds(&pos) {
-                continue;
-            }
-            let cell = &self.agent.game_map[pos];
+            let cell = match self.agent.game_map.translate(*pos, direction, 1) {
+                Some(pos) => &self.agent.game_map[pos],
+                None => continue,
+            };
             if cell.citytile.is_none() && !cell.has_resource() {
                 return Some(cell);
             }
@@ -129,14 +132,33 @@ impl Engine {
         None
     }
 
-    fn turn_cart(&mut self, cart: &Unit) -> LuxAiResult<Option<Action>> { return Ok(None) }
+    fn direction_towards(&self, unit: &Unit, target: &Position) -> Direction {
+        let path_finder = PathFinder::new(
+            &self.agent.game_map,
+            &self.agent.players,
+            unit.team,
+            unit.unit_type,
+        );
+        path_finder.find_path(unit.pos, *target).map_or_else(
+            || unit.pos.direction_to(target),
+            |path| path.first_direction(),
+        )
+    }
+
+    fn turn_cart(&mut self, _cart: &Unit) -> LuxAiResult<Option<Action>> { Ok(None) }
 
-    fn turn_citytile(&mut self, citytile: Ref<CityTile>) -> LuxAiResult<Option<Action>> {
+    fn turn_citytile(
+        &mut self, citytile: &CityTile, research: bool,
+    ) -> LuxAiResult<Option<Action>> {
         let player = self.agent.player();
-        if player.city_tile_count > player.units.len() as u32 {
+        if !research && player.city_tile_count > player.units.len() as u32 {
             return Ok(Some(citytile.build_worker()));
         }
 
+        if !player.researched_uranium() {
+            return Ok(Some(citytile.research()));
+        }
+
         Ok(None)
     }
 
@@ -148,20 +170,26 @@ impl Engine {
 
             if let Some(city) = self.closest_city_to(&worker.pos) {
                 if let Some(empty_cell) = self.empty_cell_adjacent_to(&city.pos) {
-                    return Ok(Some(worker.move_(worker.pos.direction_to(&empty_cell.pos))));
+                    return Ok(Some(
+                        worker.move_(self.direction_towards(worker, &empty_cell.pos)),
+                    ));
                 }
             }
         }
 
         if worker.get_cargo_space_left() > 0 {
             if let Some(cell) = self.closest_eligible_resource_to(&worker.pos) {
-                return Ok(Some(worker.move_(worker.pos.direction_to(&cell.pos))));
+                return Ok(Some(
+                    worker.move_(self.direction_towards(worker, &cell.pos)),
+                ));
             }
         }
 
         if worker.get_cargo_space_left() == 0 {
             if let Some(city) = self.closest_city_to(&worker.pos) {
-                return Ok(Some(worker.move_(worker.pos.direction_to(&city.pos))));
+                return Ok(Some(
+                    worker.move_(self.direction_towards(worker, &city.pos)),
+                ));
             }
         }
 
@@ -0,0 +1,16 @@
+use lux_ai::{Environment, GreedyRollout, LuxAiResult, MctsConfig, MctsPlanner, TimeBudget};
+
+fn main() -> LuxAiResult<()> {
+    let mut environment = Environment::new();
+    let mut planner = MctsPlanner::new(MctsConfig::default(), GreedyRollout);
+    environment.run_with_budget(TimeBudget::default(), &mut |agent, environment, budget| {
+        let plan = planner.plan(agent, budget);
+        for action in plan.actions {
+            environment.write_action(action);
+        }
+        for (action, rejection) in environment.validate_actions(agent) {
+            eprintln!("Dropped action `{}`: {}", action, rejection);
+        }
+        Ok(())
+    })
+}
@@ -0,0 +1,254 @@
+use std::{env, fs,
+          io::{self, BufRead, BufReader, Write},
+          process,
+          sync::mpsc,
+          thread,
+          time::Duration};
+
+use lux_ai::{Action, Commands, LuxAiResult, MapGenerator, Simulator, TeamId, TEAM_COUNT};
+use serde_json::{json, Value};
+
+const USAGE: &str = "Usage: runner <agent0> <agent1> [--seed N] [--size N] [--replay PATH] \
+                     [--timeout-ms N]";
+
+/// Match settings parsed from command line
+struct Options {
+    agents:  Vec<String>,
+    seed:    u64,
+    size:    i32,
+    replay:  String,
+    timeout: Duration,
+}
+
+impl Options {
+    fn parse() -> Result<Self, String> {
+        let mut options = Self {
+            agents:  vec![],
+            seed:    0,
+            size:    12,
+            replay:  "replay.json".to_string(),
+            timeout: Duration::from_secs(3),
+        };
+        let mut arguments = env::args().skip(1);
+        while let Some(argument) = arguments.next() {
+            let mut value = || {
+                arguments
+                    .next()
+                    .ok_or(format!("Missing value of {}", argument))
+            };
+            match argument.as_str() {
+                "--seed" => options.seed = value()?.parse().map_err(|_| "Invalid seed")?,
+                "--size" => options.size = value()?.parse().map_err(|_| "Invalid size")?,
+                "--replay" => options.replay = value()?,
+                "--timeout-ms" =>
+                    options.timeout =
+                        Duration::from_millis(value()?.parse().map_err(|_| "Invalid timeout")?),
+                _ => options.agents.push(argument),
+            }
+        }
+        if options.agents.len() != TEAM_COUNT as usize {
+            return Err(USAGE.to_string());
+        }
+        Ok(options)
+    }
+}
+
+/// Agent executable talking Lux AI API I/O through pipes
+struct AgentProcess {
+    child: process::Child,
+    stdin: process::ChildStdin,
+    lines: mpsc::Receiver<String>,
+}
+
+impl AgentProcess {
+    fn spawn(path: &str) -> LuxAiResult<Self> {
+        let mut child = process::Command::new(path)
+            .stdin(process::Stdio::piped())
+            .stdout(process::Stdio::piped())
+            .stderr(process::Stdio::inherit())
+            .spawn()?;
+        let stdin = child.stdin.take().unwrap();
+        let stdout = child.stdout.take().unwrap();
+
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(Self {
+            child,
+            stdin,
+            lines,
+        })
+    }
+
+    fn send(&mut self, lines: &[String]) -> LuxAiResult {
+        for line in lines {
+            writeln!(self.stdin, "{}", line)?;
+        }
+        self.stdin.flush()?;
+        Ok(())
+    }
+
+    /// Reads comma separated actions until [`Commands::FINISH`]
+    fn receive(&mut self, timeout: Duration) -> LuxAiResult<Vec<String>> {
+        let mut actions = vec![];
+        loop {
+            let line = self.lines.recv_timeout(timeout).map_err(|err| match err {
+                mpsc::RecvTimeoutError::Timeout =>
+                    io::Error::new(io::ErrorKind::TimedOut, "agent timed out"),
+                mpsc::RecvTimeoutError::Disconnected =>
+                    io::Error::new(io::ErrorKind::UnexpectedEof, "agent exited"),
+            })?;
+            if line.trim() == Commands::FINISH {
+                return Ok(actions);
+            }
+            actions.extend(
+                line.split(',')
+                    .map(|action| action.trim().to_string())
+                    .filter(|action| !action.is_empty()),
+            );
+        }
+    }
+}
+
+impl Drop for AgentProcess {
+    fn drop(&mut self) { self.child.kill().ok(); }
+}
+
+/// Builds one step of Kaggle episode JSON, readable by `lux_ai::Replay`
+fn episode_step(
+    simulator: &Simulator, step: usize, updates: &[String], actions: &[Vec<String>],
+) -> Value {
+    (0..TEAM_COUNT as usize)
+        .map(|team| {
+            let mut observation = json!({
+                "player": team,
+                "step": step,
+                "width": simulator.game_map.width,
+                "height": simulator.game_map.height,
+            });
+            if team == 0 {
+                observation["updates"] = json!(updates);
+            }
+            json!({ "action": actions[team], "observation": observation })
+        })
+        .collect()
+}
+
+fn run_match(options: &Options) -> LuxAiResult<Value> {
+    let mut simulator = MapGenerator::new(options.seed, options.size)?.generate();
+    let mut agents = options
+        .agents
+        .iter()
+        .map(|path| AgentProcess::spawn(path))
+        .collect::<LuxAiResult<Vec<_>>>()?;
+
+    let dimensions = format!("{} {}", simulator.game_map.width, simulator.game_map.height);
+    let header = |team: TeamId| vec![team.to_string(), dimensions.clone()];
+    let mut updates = simulator.updates();
+    let mut steps = vec![episode_step(
+        &simulator,
+        0,
+        &[header(0), updates.clone()].concat(),
+        &[vec![], vec![]],
+    )];
+    let mut failed = None;
+
+    while !simulator.is_game_over() && failed.is_none() {
+        let step = steps.len();
+        let mut submitted = vec![];
+        for (team, agent) in agents.iter_mut().enumerate() {
+            let lines = match step {
+                1 => [header(team as TeamId), updates.clone()].concat(),
+                _ => updates.clone(),
+            };
+            match agent
+                .send(&lines)
+                .and_then(|_| agent.receive(options.timeout))
+            {
+                Ok(actions) => submitted.push(actions),
+                Err(err) => {
+                    eprintln!("Agent {} failed: {}", team, err);
+                    failed = Some(team as TeamId);
+                    submitted.push(vec![]);
+                },
+            }
+        }
+
+        let actions: Vec<Vec<Action>> = submitted
+            .iter()
+            .map(|team_actions| {
+                team_actions
+                    .iter()
+                    .filter_map(|action| match action.parse::<Action>() {
+                        Ok(action) => Some(action),
+                        Err(err) => {
+                            eprintln!("Dropped action {:?}: {}", action, err);
+                            None
+                        },
+                    })
+                    .collect()
+            })
+            .collect();
+        simulator.step(&actions);
+
+        updates = simulator.updates();
+        steps.push(episode_step(&simulator, step, &updates, &submitted));
+    }
+
+    let winner = match failed {
+        Some(team) => Some(1 - team),
+        None => simulator.winner(),
+    };
+    let rewards: Vec<_> = simulator
+        .players
+        .iter()
+        .map(|player| player.city_tile_count as usize * 10_000 + player.units.len())
+        .collect();
+    Ok(json!({
+        "configuration": { "seed": options.seed, "width": options.size, "height": options.size },
+        "info": { "winner": winner, "failed": failed, "turns": simulator.turn - 1 },
+        // Kaggle reward: city tiles first, units count as tie breaker
+        "rewards": rewards,
+        "steps": steps,
+    }))
+}
+
+fn main() {
+    let options = Options::parse().unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        process::exit(2);
+    });
+
+    let result = run_match(&options).and_then(|episode| {
+        let file = fs::File::create(&options.replay)?;
+        serde_json::to_writer(io::BufWriter::new(file), &episode)?;
+        Ok(episode)
+    });
+    let episode = match result {
+        Ok(episode) => episode,
+        Err(err) => {
+            eprintln!("Match failed: {}", err);
+            process::exit(1);
+        },
+    };
+
+    println!("Turns: {}", episode["info"]["turns"]);
+    for (team, reward) in episode["rewards"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .enumerate()
+    {
+        println!("Team {}: {}", team, reward);
+    }
+    match episode["info"]["winner"].as_u64() {
+        Some(team) => println!("Winner: team {}", team),
+        None => println!("Winner: tie"),
+    }
+    println!("Replay: {}", options.replay);
+}